@@ -1,27 +1,111 @@
+mod keepachangelog;
 use cargo_metadata::semver::Version;
 use chrono::naive::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::ops::Range;
 use std::str::FromStr;
 use thiserror::Error;
 use winnow::{
     Parser,
     ascii::{Caseless, space1},
-    combinator::alt,
-    error::ModalResult,
+    combinator::{alt, opt},
+    error::{ContextError, ErrMode, ModalResult},
     stream::AsChar,
     token::{take_till, take_while},
 };
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub(crate) struct Changelog {
+    /// The format in which the changelog is written
+    #[serde(default)]
+    pub(crate) format: ChangelogFormat,
+    /// Text before the first section (Keep a Changelog format only)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) preamble: String,
     pub(crate) sections: Vec<ChangelogSection>,
+    /// Link reference definitions at the end of the changelog (Keep a
+    /// Changelog format only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) links: Vec<ChangelogLink>,
+}
+
+impl Changelog {
+    /// Add a bullet point with the text `text` to the topmost section of the
+    /// changelog, under the given category (if any).  If `replacing` is set
+    /// and the same list already contains a bullet whose text starts with
+    /// `replacing`, that bullet is replaced instead.  If the list already
+    /// contains a bullet with the exact same text, nothing is done.
+    ///
+    /// Returns `false` if the changelog has no sections.
+    pub(crate) fn add_entry(
+        &mut self,
+        text: &str,
+        category: Option<ChangelogCategory>,
+        replacing: Option<&str>,
+    ) -> bool {
+        let Some(sect) = self.sections.first_mut() else {
+            return false;
+        };
+        let mut lines = sect.content.lines().map(String::from).collect::<Vec<_>>();
+        let list = match self.format {
+            ChangelogFormat::Rsrepo => entry_list(&mut lines, category),
+            ChangelogFormat::KeepAChangelog => keepachangelog::entry_list(&mut lines, category),
+        };
+        list.add(&mut lines, text, replacing);
+        let mut content = String::new();
+        for ln in lines {
+            content.push_str(&ln);
+            content.push('\n');
+        }
+        sect.content = content;
+        true
+    }
+
+    /// Update the changelog's link reference definitions for the release of
+    /// `version` as `tag`: the "Unreleased" link (if any) is set to compare
+    /// `tag` to `HEAD`, and a link for `version` comparing `previous_tag` to
+    /// `tag` (or, if there is no previous tag, pointing to the release page
+    /// for `tag`) is added or updated.  Changelogs without any link reference
+    /// definitions are left as-is.
+    pub(crate) fn update_compare_links(
+        &mut self,
+        repo_url: &str,
+        version: &Version,
+        tag: &str,
+        previous_tag: Option<&str>,
+    ) {
+        if self.links.is_empty() {
+            return;
+        }
+        let unreleased = self
+            .links
+            .iter()
+            .position(|lnk| lnk.label.eq_ignore_ascii_case("unreleased"));
+        if let Some(i) = unreleased {
+            self.links[i].url = format!("{repo_url}/compare/{tag}...HEAD");
+        }
+        let url = match previous_tag {
+            Some(prev) => format!("{repo_url}/compare/{prev}...{tag}"),
+            None => format!("{repo_url}/releases/tag/{tag}"),
+        };
+        let label = version.to_string();
+        if let Some(lnk) = self.links.iter_mut().find(|lnk| lnk.label == label) {
+            lnk.url = url;
+        } else {
+            let i = unreleased.map_or(0, |i| i + 1);
+            self.links.insert(i, ChangelogLink { label, url });
+        }
+    }
 }
 
 impl FromStr for Changelog {
     type Err = ParseChangelogError;
 
     fn from_str(s: &str) -> Result<Changelog, ParseChangelogError> {
+        if keepachangelog::detect(s) {
+            return keepachangelog::parse(s);
+        }
         let mut sections = Vec::new();
         let mut current: Option<SectionBuilder<'_>> = None;
         let mut prev: Option<&str> = None;
@@ -53,12 +137,18 @@ impl FromStr for Changelog {
         if let Some(sb) = current.take() {
             sections.push(sb.build());
         }
-        Ok(Changelog { sections })
+        Ok(Changelog {
+            sections,
+            ..Changelog::default()
+        })
     }
 }
 
 impl fmt::Display for Changelog {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.format == ChangelogFormat::KeepAChangelog {
+            return keepachangelog::render(self, f);
+        }
         let sections = self
             .sections
             .iter()
@@ -74,6 +164,32 @@ impl fmt::Display for Changelog {
     }
 }
 
+/// The supported changelog formats.  The format of an existing changelog is
+/// detected automatically when parsing.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ChangelogFormat {
+    /// The format described in `doc/changelog-format.md`, with headers like
+    /// `vX.Y.Z (YYYY-MM-DD)` underlined with hyphens
+    #[default]
+    Rsrepo,
+    /// The [Keep a Changelog](https://keepachangelog.com) format, with
+    /// headers like `## [X.Y.Z] - YYYY-MM-DD`
+    KeepAChangelog,
+}
+
+impl ChangelogFormat {
+    /// Returns the header to use for a new section for the upcoming version
+    /// `version`
+    pub(crate) fn dev_header(self, version: Version) -> ChangelogHeader {
+        match self {
+            ChangelogFormat::Rsrepo => ChangelogHeader::InProgress { version },
+            // Keep a Changelog tracks upcoming changes under "Unreleased"
+            ChangelogFormat::KeepAChangelog => ChangelogHeader::InDevelopment,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub(crate) struct ChangelogSection {
     pub(crate) header: ChangelogHeader,
@@ -93,8 +209,16 @@ impl fmt::Display for ChangelogSection {
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "kebab-case", tag = "type")]
 pub(crate) enum ChangelogHeader {
-    Released { version: Version, date: NaiveDate },
-    InProgress { version: Version },
+    Released {
+        version: Version,
+        date: NaiveDate,
+        /// Whether the release has been yanked from the package registry
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        yanked: bool,
+    },
+    InProgress {
+        version: Version,
+    },
     InDevelopment,
 }
 
@@ -110,13 +234,135 @@ impl FromStr for ChangelogHeader {
 impl fmt::Display for ChangelogHeader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ChangelogHeader::Released { version, date } => write!(f, "v{version} ({date})"),
+            ChangelogHeader::Released {
+                version,
+                date,
+                yanked,
+            } => {
+                write!(f, "v{version} ({date})")?;
+                if *yanked {
+                    write!(f, " [YANKED]")?;
+                }
+                Ok(())
+            }
             ChangelogHeader::InProgress { version } => write!(f, "v{version} (in development)"),
             ChangelogHeader::InDevelopment => write!(f, "In Development"),
         }
     }
 }
 
+/// The categories under which changelog entries can be grouped, as defined by
+/// Keep a Changelog
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub(crate) enum ChangelogCategory {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+}
+
+impl ChangelogCategory {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ChangelogCategory::Added => "Added",
+            ChangelogCategory::Changed => "Changed",
+            ChangelogCategory::Deprecated => "Deprecated",
+            ChangelogCategory::Removed => "Removed",
+            ChangelogCategory::Fixed => "Fixed",
+            ChangelogCategory::Security => "Security",
+        }
+    }
+}
+
+impl fmt::Display for ChangelogCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A bulleted list of entries within the lines of a changelog section
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct EntryList {
+    /// The indices of the lines that make up the list
+    range: Range<usize>,
+    /// The text preceding each entry's text, e.g., `"- "`
+    marker: &'static str,
+    /// Whether the list must be separated from any following text by a blank
+    /// line
+    separated: bool,
+}
+
+impl EntryList {
+    fn add(&self, lines: &mut Vec<String>, text: &str, replacing: Option<&str>) {
+        let bullet = format!("{}{text}", self.marker);
+        let items = &lines[self.range.clone()];
+        if items.contains(&bullet) {
+            return;
+        }
+        if let Some(prefix) = replacing
+            && let Some(i) = items.iter().position(|ln| {
+                ln.strip_prefix(self.marker)
+                    .is_some_and(|s| s.starts_with(prefix))
+            })
+        {
+            lines[self.range.start + i] = bullet;
+            return;
+        }
+        let i = items
+            .iter()
+            .rposition(|ln| !ln.trim().is_empty())
+            .map_or(self.range.start, |i| self.range.start + i + 1);
+        lines.insert(i, bullet);
+        if self.separated && lines.get(i + 1).is_some_and(|ln| !ln.trim().is_empty()) {
+            lines.insert(i + 1, String::new());
+        }
+    }
+}
+
+/// Locate the list of entries for the given category in a section of an
+/// rsrepo-format changelog, adding a `- {category}:` bullet to the end of
+/// the section if there is none.  Entries in a category are written as
+/// sub-bullets of the category's bullet.
+fn entry_list(lines: &mut Vec<String>, category: Option<ChangelogCategory>) -> EntryList {
+    let Some(category) = category else {
+        return EntryList {
+            range: 0..lines.len(),
+            marker: "- ",
+            separated: false,
+        };
+    };
+    let header = format!("- {category}:");
+    let start = if let Some(i) = lines.iter().position(|ln| *ln == header) {
+        i + 1
+    } else {
+        let end = lines
+            .iter()
+            .rposition(|ln| !ln.trim().is_empty())
+            .map_or(0, |i| i + 1);
+        lines.truncate(end);
+        lines.push(header);
+        lines.len()
+    };
+    let end = lines[start..]
+        .iter()
+        .position(|ln| !ln.is_empty() && !ln.starts_with(char::is_whitespace))
+        .map_or(lines.len(), |i| start + i);
+    EntryList {
+        range: start..end,
+        marker: "    - ",
+        separated: false,
+    }
+}
+
+/// A Markdown link reference definition of the form `[label]: url`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct ChangelogLink {
+    pub(crate) label: String,
+    pub(crate) url: String,
+}
+
 #[derive(Copy, Clone, Debug, Error, Eq, PartialEq)]
 pub(crate) enum ParseChangelogError {
     #[error("unexpected hrule")]
@@ -174,17 +420,24 @@ fn parse_header(input: &mut &str) -> ModalResult<ChangelogHeader> {
 }
 
 fn versioned_header(input: &mut &str) -> ModalResult<ChangelogHeader> {
-    let (_, version, _, _, parenthed, _) = (
+    let (_, version, _, _, parenthed, _, yanked) = (
         'v',
         take_till(1.., AsChar::is_space).try_map(|s: &str| s.parse::<Version>()),
         space1,
         '(',
         alt((ymd.map(Some), Caseless("in development").map(|_| None))),
         ')',
+        opt((space1, Caseless("[yanked]"))).map(|y| y.is_some()),
     )
         .parse_next(input)?;
     if let Some(date) = parenthed {
-        Ok(ChangelogHeader::Released { version, date })
+        Ok(ChangelogHeader::Released {
+            version,
+            date,
+            yanked,
+        })
+    } else if yanked {
+        Err(ErrMode::Backtrack(ContextError::new()))
     } else {
         Ok(ChangelogHeader::InProgress { version })
     }
@@ -239,4 +492,226 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn detect_format() {
+        let chlog = "v0.1.0 (2020-07-16)\n-------------------\n## Notes\n".parse::<Changelog>();
+        assert_eq!(chlog.unwrap().format, ChangelogFormat::Rsrepo);
+        let chlog = "## [0.1.0] - 2020-07-16\n".parse::<Changelog>();
+        assert_eq!(chlog.unwrap().format, ChangelogFormat::KeepAChangelog);
+    }
+
+    mod add_entry {
+        use super::*;
+        use indoc::indoc;
+        use rstest::rstest;
+
+        #[rstest]
+        #[case::append(
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n",
+            None,
+            None,
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n- New entry\n"
+        )]
+        #[case::empty(
+            "v0.2.0 (in development)\n-----------------------\n",
+            None,
+            None,
+            "v0.2.0 (in development)\n-----------------------\n- New entry\n"
+        )]
+        #[case::duplicate(
+            "v0.2.0 (in development)\n-----------------------\n- New entry\n- Foo\n",
+            None,
+            None,
+            "v0.2.0 (in development)\n-----------------------\n- New entry\n- Foo\n"
+        )]
+        #[case::replace(
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n- New thing\n- Bar\n",
+            None,
+            Some("New "),
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n- New entry\n- Bar\n"
+        )]
+        #[case::replace_no_match(
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n",
+            None,
+            Some("Gnusto "),
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n- New entry\n"
+        )]
+        #[case::new_category(
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n",
+            Some(ChangelogCategory::Fixed),
+            None,
+            "v0.2.0 (in development)\n-----------------------\n- Foo\n- Fixed:\n    - New entry\n"
+        )]
+        #[case::existing_category(
+            indoc! {"
+                v0.2.0 (in development)
+                -----------------------
+                - Added:
+                    - Foo
+                - Fixed:
+                    - Bar
+                - Baz
+            "},
+            Some(ChangelogCategory::Added),
+            None,
+            indoc! {"
+                v0.2.0 (in development)
+                -----------------------
+                - Added:
+                    - Foo
+                    - New entry
+                - Fixed:
+                    - Bar
+                - Baz
+            "},
+        )]
+        #[case::keepachangelog_append(
+            "## [Unreleased]\n\n- Foo\n",
+            None,
+            None,
+            "## [Unreleased]\n\n- Foo\n- New entry\n"
+        )]
+        #[case::keepachangelog_empty(
+            "## [Unreleased]\n\n## [0.1.0] - 2020-07-16\n",
+            Some(ChangelogCategory::Added),
+            None,
+            "## [Unreleased]\n\n### Added\n- New entry\n\n## [0.1.0] - 2020-07-16\n"
+        )]
+        #[case::keepachangelog_uncategorized(
+            "## [Unreleased]\n\n### Added\n- Foo\n",
+            None,
+            None,
+            "## [Unreleased]\n\n- New entry\n\n### Added\n- Foo\n"
+        )]
+        #[case::keepachangelog_new_category(
+            "## [Unreleased]\n\n### Added\n- Foo\n",
+            Some(ChangelogCategory::Fixed),
+            None,
+            "## [Unreleased]\n\n### Added\n- Foo\n\n### Fixed\n- New entry\n"
+        )]
+        #[case::keepachangelog_existing_category(
+            indoc! {"
+                ## [Unreleased]
+
+                ### Added
+                - Foo
+
+                ### Fixed
+                - Bar
+                - Old entry
+            "},
+            Some(ChangelogCategory::Fixed),
+            Some("Old "),
+            indoc! {"
+                ## [Unreleased]
+
+                ### Added
+                - Foo
+
+                ### Fixed
+                - Bar
+                - New entry
+            "},
+        )]
+        fn test(
+            #[case] src: &str,
+            #[case] category: Option<ChangelogCategory>,
+            #[case] replacing: Option<&str>,
+            #[case] expected: &str,
+        ) {
+            let mut chlog = src.parse::<Changelog>().unwrap();
+            assert!(chlog.add_entry("New entry", category, replacing));
+            assert_eq!(chlog.to_string(), expected);
+        }
+
+        #[test]
+        fn no_sections() {
+            let mut chlog = "".parse::<Changelog>().unwrap();
+            assert!(!chlog.add_entry("New entry", None, None));
+        }
+    }
+
+    mod update_compare_links {
+        use super::*;
+
+        fn links(chlog: &Changelog) -> Vec<(&str, &str)> {
+            chlog
+                .links
+                .iter()
+                .map(|lnk| (&*lnk.label, &*lnk.url))
+                .collect()
+        }
+
+        #[test]
+        fn unreleased() {
+            let mut chlog = concat!(
+                "## [Unreleased]\n",
+                "\n",
+                "[unreleased]: https://github.com/octocat/foobar/compare/v0.1.0...HEAD\n",
+                "[0.1.0]: https://github.com/octocat/foobar/releases/tag/v0.1.0\n",
+            )
+            .parse::<Changelog>()
+            .unwrap();
+            chlog.update_compare_links(
+                "https://github.com/octocat/foobar",
+                &Version::new(0, 2, 0),
+                "v0.2.0",
+                Some("v0.1.0"),
+            );
+            assert_eq!(
+                links(&chlog),
+                [
+                    (
+                        "unreleased",
+                        "https://github.com/octocat/foobar/compare/v0.2.0...HEAD"
+                    ),
+                    (
+                        "0.2.0",
+                        "https://github.com/octocat/foobar/compare/v0.1.0...v0.2.0"
+                    ),
+                    (
+                        "0.1.0",
+                        "https://github.com/octocat/foobar/releases/tag/v0.1.0"
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn first_release() {
+            let mut chlog = concat!(
+                "## [0.1.0] - Unreleased\n",
+                "\n",
+                "[0.1.0]: https://example.com\n",
+            )
+            .parse::<Changelog>()
+            .unwrap();
+            chlog.update_compare_links(
+                "https://github.com/octocat/foobar",
+                &Version::new(0, 1, 0),
+                "foobar/v0.1.0",
+                None,
+            );
+            assert_eq!(
+                links(&chlog),
+                [(
+                    "0.1.0",
+                    "https://github.com/octocat/foobar/releases/tag/foobar/v0.1.0"
+                )]
+            );
+        }
+
+        #[test]
+        fn no_links() {
+            let mut chlog = "## [Unreleased]\n".parse::<Changelog>().unwrap();
+            chlog.update_compare_links(
+                "https://github.com/octocat/foobar",
+                &Version::new(0, 1, 0),
+                "v0.1.0",
+                None,
+            );
+            assert!(chlog.links.is_empty());
+        }
+    }
 }
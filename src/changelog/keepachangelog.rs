@@ -0,0 +1,222 @@
+//! Parsing & rendering of changelogs in the [Keep a Changelog][kac] format
+//!
+//! [kac]: https://keepachangelog.com
+use super::{
+    Changelog, ChangelogCategory, ChangelogFormat, ChangelogHeader, ChangelogLink, EntryList,
+    ParseChangelogError, ParseHeaderError, SectionBuilder, ymd,
+};
+use cargo_metadata::semver::Version;
+use std::fmt;
+use winnow::{
+    Parser,
+    ascii::{Caseless, space0, space1},
+    combinator::{alt, delimited, opt},
+    error::{ContextError, ErrMode, ModalResult},
+    token::take_till,
+};
+
+/// Returns true if `s` contains a level-2 heading that parses as a Keep a
+/// Changelog section header
+pub(super) fn detect(s: &str) -> bool {
+    s.lines()
+        .filter_map(|ln| ln.strip_prefix("## "))
+        .any(|title| parse_header(title).is_ok())
+}
+
+pub(super) fn parse(s: &str) -> Result<Changelog, ParseChangelogError> {
+    let mut lines = s.lines().collect::<Vec<_>>();
+    let mut links = Vec::new();
+    while let Some(&ln) = lines.last() {
+        if ln.trim().is_empty() {
+            lines.pop();
+        } else if let Some(lnk) = parse_link(ln) {
+            links.push(lnk);
+            lines.pop();
+        } else {
+            break;
+        }
+    }
+    links.reverse();
+    let mut preamble = Vec::new();
+    let mut sections = Vec::new();
+    let mut current: Option<SectionBuilder<'_>> = None;
+    for ln in lines {
+        if let Some(title) = ln.strip_prefix("## ") {
+            if let Some(sb) = current.take() {
+                sections.push(sb.build());
+            }
+            current = Some(SectionBuilder::new(parse_header(title)?));
+        } else if let Some(sb) = current.as_mut() {
+            // Blank lines between the header and the content are not
+            // considered part of the content
+            if !(sb.lines.is_empty() && ln.trim().is_empty()) {
+                sb.push_line(ln);
+            }
+        } else {
+            preamble.push(ln);
+        }
+    }
+    if let Some(sb) = current.take() {
+        sections.push(sb.build());
+    }
+    while preamble.last().is_some_and(|ln| ln.trim().is_empty()) {
+        preamble.pop();
+    }
+    let mut preamble = preamble.join("\n");
+    if !preamble.is_empty() {
+        preamble.push('\n');
+    }
+    Ok(Changelog {
+        format: ChangelogFormat::KeepAChangelog,
+        preamble,
+        sections,
+        links,
+    })
+}
+
+pub(super) fn render(chlog: &Changelog, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut first = true;
+    if !chlog.preamble.is_empty() {
+        write!(f, "{}", chlog.preamble)?;
+        first = false;
+    }
+    for sect in &chlog.sections {
+        if !std::mem::replace(&mut first, false) {
+            writeln!(f)?;
+        }
+        writeln!(f, "## {}", HeaderDisplay(&sect.header))?;
+        if !sect.content.is_empty() {
+            writeln!(f)?;
+            write!(f, "{}", sect.content)?;
+        }
+    }
+    if !chlog.links.is_empty() {
+        if !first {
+            writeln!(f)?;
+        }
+        for lnk in &chlog.links {
+            writeln!(f, "[{}]: {}", lnk.label, lnk.url)?;
+        }
+    }
+    Ok(())
+}
+
+/// Locate the list of entries for the given category in a section, adding a
+/// `### {category}` heading to the end of the section if there is none.
+/// Uncategorized entries go before the section's first `###` heading.
+pub(super) fn entry_list(
+    lines: &mut Vec<String>,
+    category: Option<ChangelogCategory>,
+) -> EntryList {
+    let start = match category {
+        None => 0,
+        Some(category) => {
+            let found = lines.iter().position(|ln| {
+                ln.strip_prefix("### ")
+                    .is_some_and(|title| title.trim().eq_ignore_ascii_case(category.as_str()))
+            });
+            if let Some(i) = found {
+                i + 1
+            } else {
+                let end = lines
+                    .iter()
+                    .rposition(|ln| !ln.trim().is_empty())
+                    .map_or(0, |i| i + 1);
+                lines.truncate(end);
+                if !lines.is_empty() {
+                    lines.push(String::new());
+                }
+                lines.push(format!("### {category}"));
+                lines.len()
+            }
+        }
+    };
+    let end = lines[start..]
+        .iter()
+        .position(|ln| ln.starts_with("### "))
+        .map_or(lines.len(), |i| start + i);
+    EntryList {
+        range: start..end,
+        marker: "- ",
+        separated: true,
+    }
+}
+
+/// Displays a section header in Keep a Changelog format (without the leading
+/// `## `)
+struct HeaderDisplay<'a>(&'a ChangelogHeader);
+
+impl fmt::Display for HeaderDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ChangelogHeader::Released {
+                version,
+                date,
+                yanked,
+            } => {
+                write!(f, "[{version}] - {date}")?;
+                if *yanked {
+                    write!(f, " [YANKED]")?;
+                }
+                Ok(())
+            }
+            ChangelogHeader::InProgress { version } => write!(f, "[{version}] - Unreleased"),
+            ChangelogHeader::InDevelopment => write!(f, "[Unreleased]"),
+        }
+    }
+}
+
+fn parse_header(s: &str) -> Result<ChangelogHeader, ParseHeaderError> {
+    alt((versioned_header, unreleased))
+        .parse(s.trim_end())
+        .map_err(|_| ParseHeaderError)
+}
+
+fn versioned_header(input: &mut &str) -> ModalResult<ChangelogHeader> {
+    let (version, _, _, _, dated, yanked) = (
+        alt((
+            delimited('[', take_till(1.., ']'), ']'),
+            take_till(1.., |c: char| c.is_whitespace()),
+        ))
+        .try_map(|s: &str| s.parse::<Version>()),
+        space1,
+        '-',
+        space1,
+        alt((ymd.map(Some), Caseless("unreleased").map(|_| None))),
+        opt((space1, Caseless("[yanked]"))).map(|y| y.is_some()),
+    )
+        .parse_next(input)?;
+    if let Some(date) = dated {
+        Ok(ChangelogHeader::Released {
+            version,
+            date,
+            yanked,
+        })
+    } else if yanked {
+        Err(ErrMode::Backtrack(ContextError::new()))
+    } else {
+        Ok(ChangelogHeader::InProgress { version })
+    }
+}
+
+fn unreleased(input: &mut &str) -> ModalResult<ChangelogHeader> {
+    alt((
+        ('[', space0, Caseless("unreleased"), space0, ']').void(),
+        Caseless("unreleased").void(),
+    ))
+    .map(|()| ChangelogHeader::InDevelopment)
+    .parse_next(input)
+}
+
+/// Parse a line of the form `[label]: url`
+fn parse_link(line: &str) -> Option<ChangelogLink> {
+    let (label, url) = line.strip_prefix('[')?.split_once("]:")?;
+    let url = url.trim();
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(ChangelogLink {
+        label: label.to_owned(),
+        url: url.to_owned(),
+    })
+}
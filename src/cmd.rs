@@ -37,6 +37,11 @@ impl LoggedCommand {
         self
     }
 
+    pub(crate) fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, value: V) -> &mut Self {
+        self.cmd.env(key, value);
+        self
+    }
+
     pub(crate) fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
         // TODO: Include the dir in the log message?
         self.cmd.current_dir(dir);
@@ -1,15 +1,25 @@
 mod begin_dev;
+mod changelog;
 mod inspect;
+mod lint_manifest;
 mod mkgithub;
 mod new;
+mod publish_release;
 mod release;
 mod set_msrv;
+mod unrelease;
+mod yank;
 use self::begin_dev::BeginDev;
+use self::changelog::Changelog;
 use self::inspect::Inspect;
+use self::lint_manifest::LintManifest;
 use self::mkgithub::Mkgithub;
 use self::new::New;
+use self::publish_release::PublishRelease;
 use self::release::Release;
 use self::set_msrv::SetMsrv;
+use self::unrelease::Unrelease;
+use self::yank::Yank;
 use crate::provider::Provider;
 use clap::Subcommand;
 
@@ -17,10 +27,15 @@ use clap::Subcommand;
 pub(crate) enum Command {
     New(New),
     BeginDev(BeginDev),
+    Changelog(Changelog),
     Inspect(Inspect),
+    LintManifest(LintManifest),
     Mkgithub(Mkgithub),
+    PublishRelease(PublishRelease),
     Release(Release),
     SetMsrv(SetMsrv),
+    Unrelease(Unrelease),
+    Yank(Yank),
 }
 
 impl Command {
@@ -28,10 +43,15 @@ impl Command {
         match self {
             Command::New(new) => new.run(provider),
             Command::BeginDev(begin_dev) => begin_dev.run(provider),
+            Command::Changelog(chlog) => chlog.run(provider),
             Command::Inspect(inspect) => inspect.run(provider),
+            Command::LintManifest(lm) => lm.run(provider),
             Command::Mkgithub(mg) => mg.run(provider),
+            Command::PublishRelease(pr) => pr.run(provider),
             Command::Release(r) => r.run(provider),
             Command::SetMsrv(sm) => sm.run(provider),
+            Command::Unrelease(u) => u.run(provider),
+            Command::Yank(y) => y.run(provider),
         }
     }
 }
@@ -1,17 +1,98 @@
-use crate::project::Project;
+use crate::project::{Package, PackageSet, Project};
 use crate::provider::Provider;
+use crate::util::{Bump, bump_version, parse_v_version};
+use anyhow::bail;
+use cargo_metadata::semver::Version;
 use clap::Args;
 
 /// Begin work on the next version of the project
 #[derive(Args, Clone, Debug, Eq, PartialEq)]
-pub(crate) struct BeginDev;
+pub(crate) struct BeginDev {
+    /// Begin work on the next major version after the latest release
+    #[arg(long, conflicts_with = "patch")]
+    major: bool,
+
+    /// Begin work on the package with the given name in the workspace.
+    ///
+    /// By default, the package for the current directory is used.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+
+    /// Begin work on the next patch version after the latest release
+    #[arg(long)]
+    patch: bool,
+
+    /// Begin work on the next version of every package in the workspace
+    #[arg(short, long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// The version to begin work on, without a "-dev" suffix.  A leading "v"
+    /// is ignored.
+    #[arg(value_name = "VERSION", value_parser = parse_v_version, conflicts_with_all = ["major", "patch"])]
+    version: Option<Version>,
+}
 
 impl BeginDev {
     pub(crate) fn run(self, _provider: Provider) -> anyhow::Result<()> {
+        if let Some(ref v) = self.version
+            && !v.pre.is_empty()
+        {
+            bail!("VERSION must not contain a prerelease segment");
+        }
         let project = Project::locate()?;
         let pkgset = project.package_set()?;
-        let package = pkgset.get(None)?;
-        package.begin_dev(&pkgset).run()?;
+        let packages = if self.workspace {
+            pkgset.dependency_order()?
+        } else {
+            vec![pkgset.get(self.package.as_deref())?]
+        };
+        for package in packages {
+            if self.workspace {
+                log::info!("Beginning development on {} ...", package.name());
+            }
+            let begin_dev = package.begin_dev(&pkgset);
+            if let Some(ref v) = self.version {
+                begin_dev.version(v.clone()).force(true).run()?;
+            } else if let Some(level) = self.level() {
+                let base = latest_release(&project, &pkgset, package)?;
+                begin_dev
+                    .version(bump_version(base, level))
+                    .force(true)
+                    .run()?;
+            } else {
+                begin_dev.run()?;
+            }
+        }
         Ok(())
     }
+
+    fn level(&self) -> Option<Bump> {
+        if self.major {
+            Some(Bump::Major)
+        } else if self.patch {
+            Some(Bump::Patch)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the version of the package's latest release: the package's current
+/// version if it is not a dev version, or else the version of the package's
+/// latest release tag (falling back to the current version sans prerelease if
+/// there are no tags)
+fn latest_release(
+    project: &Project,
+    pkgset: &PackageSet,
+    package: &Package,
+) -> anyhow::Result<Version> {
+    let current = &package.metadata().version;
+    if current.pre.is_empty() {
+        return Ok(current.clone());
+    }
+    let tag_prefix = project.tag_prefix(pkgset, package);
+    match project.git().latest_tag_version(tag_prefix.as_deref())? {
+        Some(v) => Ok(Version::new(v.major, v.minor, v.patch)),
+        None => Ok(Version::new(current.major, current.minor, current.patch)),
+    }
 }
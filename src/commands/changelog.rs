@@ -0,0 +1,84 @@
+use crate::changelog::ChangelogCategory;
+use crate::project::Project;
+use crate::provider::Provider;
+use anyhow::bail;
+use clap::{Args, Subcommand};
+
+/// Edit packages' changelogs
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Changelog {
+    #[command(subcommand)]
+    command: ChangelogCommand,
+}
+
+impl Changelog {
+    pub(crate) fn run(self, provider: Provider) -> anyhow::Result<()> {
+        match self.command {
+            ChangelogCommand::Add(add) => add.run(provider),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Subcommand)]
+enum ChangelogCommand {
+    Add(Add),
+}
+
+/// Add an entry to the changelog section for the upcoming version
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+struct Add {
+    /// Add the entry under the given category
+    #[arg(short, long, value_enum, ignore_case = true)]
+    category: Option<ChangelogCategory>,
+
+    /// Add the entry to the changelog of the package with the given name in
+    /// the workspace.
+    ///
+    /// By default, the package for the current directory is used.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+
+    /// Replace the first entry in the same list whose text starts with the
+    /// given prefix, if there is one, instead of adding a new entry
+    #[arg(long, value_name = "PREFIX")]
+    replace: Option<String>,
+
+    /// Add the entry to the changelog of every package in the workspace that
+    /// has one
+    #[arg(short, long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// The text of the entry, without a leading bullet
+    #[arg(value_name = "TEXT")]
+    text: String,
+}
+
+impl Add {
+    fn run(self, _provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        let pkgset = project.package_set()?;
+        if self.workspace {
+            for package in pkgset.dependency_order()? {
+                if !package.add_changelog_entry(
+                    &pkgset,
+                    &self.text,
+                    self.category,
+                    self.replace.as_deref(),
+                )? {
+                    log::debug!("{} does not have a CHANGELOG.md; skipping", package.name());
+                }
+            }
+        } else {
+            let package = pkgset.get(self.package.as_deref())?;
+            if !package.add_changelog_entry(
+                &pkgset,
+                &self.text,
+                self.category,
+                self.replace.as_deref(),
+            )? {
+                bail!("{} does not have a CHANGELOG.md", package.name());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::project::Project;
+use crate::provider::Provider;
+use anyhow::bail;
+use clap::Args;
+
+/// Check package metadata for problems before publishing
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LintManifest {
+    /// Check the package with the given name in the workspace.
+    ///
+    /// By default, the package for the current directory is checked.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+
+    /// Check all public packages in the workspace
+    #[arg(short, long, conflicts_with = "package")]
+    workspace: bool,
+}
+
+impl LintManifest {
+    pub(crate) fn run(self, _provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        let pkgset = project.package_set()?;
+        let packages = if self.workspace {
+            pkgset.iter().filter(|p| p.is_public()).collect::<Vec<_>>()
+        } else {
+            vec![pkgset.get(self.package.as_deref())?]
+        };
+        let mut failed = false;
+        for package in packages {
+            for p in package.lint_manifest() {
+                log::error!("{}: {p}", package.name());
+                failed = true;
+            }
+        }
+        if failed {
+            bail!("Problems found in package metadata");
+        }
+        log::info!("No problems found");
+        Ok(())
+    }
+}
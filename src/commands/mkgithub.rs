@@ -166,6 +166,8 @@ impl GitHubMaker {
             bail!("Could not determine repository's default branch");
         };
 
+        let remote = self.provider.remote(self.root_package.as_ref())?;
+
         let codecov_token = self.codecov_token_source.resolve(&self.provider)?;
 
         Ok(Plan {
@@ -175,6 +177,7 @@ impl GitHubMaker {
             topics,
             required_checks,
             default_branch,
+            remote,
             codecov_token,
             metadata_repo_url: flavor.repository,
         })
@@ -193,11 +196,11 @@ impl GitHubMaker {
 
         log::info!("Setting remote and pushing");
         let git = self.project.git();
-        if git.remotes()?.contains("origin") {
-            git.rm_remote("origin")?;
+        if git.remotes()?.contains(&plan.remote) {
+            git.rm_remote(&plan.remote)?;
         }
-        git.add_remote("origin", &repo.ssh_url)?;
-        git.run("push", ["-u", "origin", "refs/heads/*", "refs/tags/*"])?;
+        git.add_remote(&plan.remote, &repo.ssh_url)?;
+        git.run("push", ["-u", &plan.remote, "refs/heads/*", "refs/tags/*"])?;
 
         let topics = plan.topics;
         log::info!(
@@ -278,6 +281,8 @@ struct Plan {
     repo_name: String,
     metadata_repo_url: Option<String>,
     default_branch: &'static str,
+    /// The Git remote to point at the new repository
+    remote: String,
     description: Option<String>,
     topics: Vec<Topic>,
     private: bool,
@@ -0,0 +1,27 @@
+use super::release::Releaser;
+use crate::project::Project;
+use crate::provider::Provider;
+use clap::Args;
+
+/// Publish a draft GitHub release
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PublishRelease {
+    /// Use the release tags of the package with the given name in the
+    /// workspace.
+    ///
+    /// By default, the package for the current directory is used.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+
+    /// The tag of the release to publish.  Defaults to the package's most
+    /// recent release tag.
+    tag: Option<String>,
+}
+
+impl PublishRelease {
+    pub(crate) fn run(self, provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        Releaser::new(project, provider)?
+            .publish_release(self.package.as_deref(), self.tag.as_deref())
+    }
+}
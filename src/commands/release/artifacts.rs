@@ -0,0 +1,155 @@
+use crate::cmd::LoggedCommand;
+use crate::project::Package;
+use anyhow::{Context, bail};
+use cargo_metadata::{Message, TargetKind, semver::Version};
+use flate2::{Compression, write::GzEncoder};
+use fs_err::File;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// A binary built by `cargo build`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Binary {
+    pub(super) name: String,
+    pub(super) path: PathBuf,
+}
+
+/// Returns the target triple of the host, as reported by `rustc -vV`
+pub(super) fn host_target() -> anyhow::Result<String> {
+    let output = LoggedCommand::new("rustc").arg("-vV").check_output()?;
+    output
+        .lines()
+        .find_map(|ln| ln.strip_prefix("host: "))
+        .map(|s| s.trim().to_owned())
+        .context("`rustc -vV` output did not contain host target")
+}
+
+/// Returns the targets installed via rustup, or an empty list if rustup is
+/// not installed
+pub(super) fn installed_targets() -> anyhow::Result<Vec<String>> {
+    if which::which("rustup").is_err() {
+        return Ok(Vec::new());
+    }
+    let output = LoggedCommand::new("rustup")
+        .arg("target")
+        .arg("list")
+        .arg("--installed")
+        .check_output()?;
+    Ok(output
+        .lines()
+        .map(str::trim)
+        .filter(|ln| !ln.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Run `cargo build --release` for `package` and `target` and return the
+/// binaries that were built
+pub(super) fn build(package: &Package, target: &str) -> anyhow::Result<Vec<Binary>> {
+    log::info!("Building {} binaries for {target} ...", package.name());
+    let output = LoggedCommand::new("cargo")
+        .arg("build")
+        .arg("--release")
+        .arg("--message-format=json")
+        .arg("--manifest-path")
+        .arg(package.manifest_path())
+        .arg("--target")
+        .arg(target)
+        .check_output()?;
+    let mut binaries = Vec::new();
+    for msg in Message::parse_stream(output.as_bytes()) {
+        if let Message::CompilerArtifact(artifact) =
+            msg.context("failed to parse `cargo build` output")?
+            && artifact.target.kind.contains(&TargetKind::Bin)
+            && artifact.manifest_path.as_std_path() == package.manifest_path()
+            && let Some(path) = artifact.executable
+        {
+            binaries.push(Binary {
+                name: artifact.target.name,
+                path: path.into_std_path_buf(),
+            });
+        }
+    }
+    if binaries.is_empty() {
+        bail!("`cargo build` did not produce any binaries for {target}");
+    }
+    Ok(binaries)
+}
+
+/// Create a `.tar.gz` archive in `outdir` containing the given binary along
+/// with the package's README and license files (if any) inside a top-level
+/// directory named after the archive.  Returns the path to the archive.
+pub(super) fn archive(
+    outdir: &Path,
+    package: &Package,
+    binary: &Binary,
+    version: &Version,
+    target: &str,
+) -> anyhow::Result<PathBuf> {
+    let basename = format!("{}-{version}-{target}", binary.name);
+    let archive_path = outdir.join(format!("{basename}.tar.gz"));
+    log::info!("Creating {} ...", archive_path.display());
+    let mut tarball = tar::Builder::new(GzEncoder::new(
+        File::create(&archive_path)?,
+        Compression::default(),
+    ));
+    let Some(exe_name) = binary.path.file_name() else {
+        bail!("Binary path {} lacks a file name", binary.path.display());
+    };
+    tarball.append_path_with_name(&binary.path, Path::new(&basename).join(exe_name))?;
+    for fname in ["README.md", "LICENSE"] {
+        let path = package.path().join(fname);
+        if path.exists() {
+            tarball.append_path_with_name(&path, Path::new(&basename).join(fname))?;
+        }
+    }
+    tarball.into_inner()?.finish()?;
+    Ok(archive_path)
+}
+
+/// Write the SHA-256 checksum of `path` to a file alongside it with a
+/// `.sha256` extension appended, in the format used by `sha256sum`.  Returns
+/// the path to the checksum file.
+pub(super) fn write_checksum(path: &Path) -> anyhow::Result<PathBuf> {
+    let Some(fname) = path.file_name() else {
+        bail!("Path {} lacks a file name", path.display());
+    };
+    let digest = Sha256::digest(fs_err::read(path)?);
+    let mut line = String::with_capacity(64);
+    for b in digest {
+        let _ = write!(&mut line, "{b:02x}");
+    }
+    let _ = writeln!(&mut line, "  {}", fname.to_string_lossy());
+    let mut checksum_path = path.as_os_str().to_owned();
+    checksum_path.push(".sha256");
+    let checksum_path = PathBuf::from(checksum_path);
+    fs_err::write(&checksum_path, line)?;
+    Ok(checksum_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn checksum() {
+        let tmp_path = tempdir().unwrap();
+        let path = tmp_path
+            .path()
+            .join("foo-1.0.0-x86_64-unknown-linux-gnu.tar.gz");
+        fs_err::write(&path, "Hello, world!\n").unwrap();
+        let checksum_path = write_checksum(&path).unwrap();
+        assert_eq!(
+            checksum_path,
+            tmp_path
+                .path()
+                .join("foo-1.0.0-x86_64-unknown-linux-gnu.tar.gz.sha256")
+        );
+        assert_eq!(
+            fs_err::read_to_string(checksum_path).unwrap(),
+            "d9014c4624844aa5bac314773d6b689ad467fa4e1d1a50a1b8a99d5a95f72ff5  foo-1.0.0-x86_64-unknown-linux-gnu.tar.gz\n"
+        );
+    }
+}
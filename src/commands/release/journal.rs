@@ -0,0 +1,259 @@
+use super::Plan;
+use crate::git::Git;
+use anyhow::Context;
+use fs_err::{read_to_string, remove_file, write};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+static JOURNAL_FILENAME: &str = "rsrepo-release.json";
+
+/// A record of the progress of a release of one or more packages, saved in
+/// the Git directory so that an interrupted release can be resumed
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(super) struct Journal {
+    /// The packages being released, in the order in which they are released
+    pub(super) entries: Vec<JournalEntry>,
+    /// If set, the release stops before pushing so that it can be reviewed
+    /// and then finished with `rsrepo release --push`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) hold: Option<Hold>,
+}
+
+impl Journal {
+    pub(super) fn new(plans: Vec<Plan>) -> Journal {
+        Journal {
+            entries: plans.into_iter().map(JournalEntry::new).collect(),
+            hold: None,
+        }
+    }
+
+    pub(super) fn hold(mut self, hold: Option<Hold>) -> Journal {
+        self.hold = hold;
+        self
+    }
+
+    /// Returns a human-readable list of the packages & versions being released
+    pub(super) fn describe(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{} {}", entry.plan.package, entry.plan.new_version))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The point before pushing at which a held release stops
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum Hold {
+    /// Stop after tagging, without publishing
+    AfterTag,
+    /// Stop after publishing
+    AfterPublish,
+}
+
+/// The progress of the release of a single package
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(super) struct JournalEntry {
+    pub(super) plan: Plan,
+    completed: Vec<Step>,
+    /// The object ID of the release commit, once it's been made
+    #[serde(default)]
+    pub(super) release_commit: Option<String>,
+    /// Whether there were no uncommitted changes to tracked files when work
+    /// on the package began, in which case the release changes can be
+    /// discarded if the release fails before the release commit is made
+    #[serde(default)]
+    pub(super) clean_start: bool,
+}
+
+impl JournalEntry {
+    pub(super) fn new(plan: Plan) -> JournalEntry {
+        JournalEntry {
+            plan,
+            completed: Vec::new(),
+            release_commit: None,
+            clean_start: false,
+        }
+    }
+
+    pub(super) fn is_done(&self, step: Step) -> bool {
+        self.completed.contains(&step)
+    }
+
+    pub(super) fn is_started(&self) -> bool {
+        !self.completed.is_empty()
+    }
+
+    pub(super) fn mark_done(&mut self, step: Step) {
+        if !self.is_done(step) {
+            self.completed.push(step);
+        }
+    }
+
+    /// Returns true if nothing has been pushed or published yet and either
+    /// the release commit has been made or work on the package began from a
+    /// clean working tree, in which case the release commit and tag (or the
+    /// uncommitted release changes) can be safely discarded
+    pub(super) fn can_roll_back(&self) -> bool {
+        (self.is_done(Step::Commit) || self.clean_start)
+            && !self.is_done(Step::Push)
+            && !(self.plan.publish && self.is_done(Step::Publish))
+    }
+}
+
+/// The individual steps of a release, in the order in which they are
+/// performed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum Step {
+    /// Set the version and update the changelog, README, and LICENSE
+    Prepare,
+    /// Check that the package can be packaged for publishing
+    CheckPackage,
+    Commit,
+    Tag,
+    VerifyTag,
+    Publish,
+    PostPublish,
+    Push,
+    #[serde(rename = "github-release")]
+    GitHubRelease,
+    UploadArtifacts,
+    Topics,
+    BeginDev,
+    PostRelease,
+}
+
+impl Step {
+    /// The steps performed for each package before pushing
+    pub(super) const PRE_PUSH: [Step; 7] = [
+        Step::Prepare,
+        Step::CheckPackage,
+        Step::Commit,
+        Step::Tag,
+        Step::VerifyTag,
+        Step::Publish,
+        Step::PostPublish,
+    ];
+
+    /// The steps performed for each package after pushing
+    pub(super) const POST_PUSH: [Step; 5] = [
+        Step::GitHubRelease,
+        Step::UploadArtifacts,
+        Step::Topics,
+        Step::BeginDev,
+        Step::PostRelease,
+    ];
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct JournalFile {
+    path: PathBuf,
+}
+
+impl JournalFile {
+    pub(super) fn for_repo(git: Git<'_>) -> anyhow::Result<JournalFile> {
+        let git_dir = git
+            .git_dir()
+            .context("Could not determine Git repository's .git directory")?;
+        Ok(JournalFile {
+            path: git_dir.join(JOURNAL_FILENAME),
+        })
+    }
+
+    pub(super) fn load(&self) -> anyhow::Result<Option<Journal>> {
+        match read_to_string(&self.path) {
+            Ok(s) => serde_json::from_str(&s)
+                .with_context(|| format!("failed to parse {}", self.path.display()))
+                .map(Some),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub(super) fn save(&self, journal: &Journal) -> anyhow::Result<()> {
+        let src = serde_json::to_string_pretty(journal)?;
+        write(&self.path, src + "\n")
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+
+    pub(super) fn remove(&self) -> anyhow::Result<()> {
+        match remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> JournalEntry {
+        JournalEntry::new(
+            serde_json::from_str(include_str!("../../testdata/release/plan.json")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn mark_done() {
+        let mut entry = entry();
+        assert!(!entry.is_started());
+        assert!(!entry.is_done(Step::Prepare));
+        entry.mark_done(Step::Prepare);
+        entry.mark_done(Step::Commit);
+        entry.mark_done(Step::Prepare);
+        assert!(entry.is_started());
+        assert!(entry.is_done(Step::Prepare));
+        assert!(entry.is_done(Step::Commit));
+        assert!(!entry.is_done(Step::Tag));
+        assert_eq!(entry.completed, [Step::Prepare, Step::Commit]);
+    }
+
+    #[test]
+    fn can_roll_back() {
+        let mut entry = entry();
+        entry.mark_done(Step::Prepare);
+        assert!(!entry.can_roll_back());
+        entry.clean_start = true;
+        assert!(entry.can_roll_back());
+        entry.clean_start = false;
+        entry.mark_done(Step::Commit);
+        assert!(entry.can_roll_back());
+        entry.mark_done(Step::Tag);
+        assert!(entry.can_roll_back());
+        entry.mark_done(Step::Publish);
+        assert!(!entry.can_roll_back());
+        entry.plan.publish = false;
+        assert!(entry.can_roll_back());
+        entry.mark_done(Step::Push);
+        assert!(!entry.can_roll_back());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut entry = entry();
+        entry.mark_done(Step::Prepare);
+        entry.mark_done(Step::GitHubRelease);
+        let journal = Journal {
+            entries: vec![entry],
+            hold: None,
+        };
+        assert_eq!(journal.describe(), "fibcore 0.3.0");
+        let src = serde_json::to_string(&journal).unwrap();
+        assert!(src.contains(r#""completed":["prepare","github-release"]"#));
+        assert!(!src.contains("hold"));
+        assert_eq!(serde_json::from_str::<Journal>(&src).unwrap(), journal);
+    }
+
+    #[test]
+    fn roundtrip_hold() {
+        let journal = Journal::new(vec![entry().plan]).hold(Some(Hold::AfterPublish));
+        let src = serde_json::to_string(&journal).unwrap();
+        assert!(src.contains(r#""hold":"after-publish""#));
+        assert_eq!(serde_json::from_str::<Journal>(&src).unwrap(), journal);
+    }
+}
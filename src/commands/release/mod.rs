@@ -0,0 +1,1864 @@
+mod artifacts;
+mod journal;
+mod notes;
+mod packaging;
+mod preflight;
+use self::journal::{Hold, Journal, JournalEntry, JournalFile, Step};
+use crate::changelog::{ChangelogHeader, ChangelogSection};
+use crate::cmd::LoggedCommand;
+use crate::config::{Hook, Hooks, PublishStrategy, SignMethod};
+use crate::github::{CreateRelease, Topic, UpdateRelease};
+use crate::project::{HasReadme, Package, PackageSet, Project};
+use crate::provider::Provider;
+use crate::readme::{Badge, Repostatus};
+use crate::util::{
+    Bump, PreLevel, bump_prerelease, bump_version, move_dirtree_into, parse_v_version, today,
+};
+use anyhow::{Context, bail};
+use cargo_metadata::semver::{Version, VersionReq};
+use chrono::{Datelike, NaiveDate};
+use clap::Args;
+use ghrepo::{GHRepo, LocalRepo};
+use renamore::rename_exclusive;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Prepare & publish a new release for a package
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Release {
+    /// Build release binaries for the host target and any other installed
+    /// targets and attach them to the GitHub release
+    #[arg(long)]
+    artifacts: bool,
+
+    #[command(flatten)]
+    pub(crate) bumping: Bumping,
+
+    /// Use the given date (in YYYY-MM-DD format) as the release date in the
+    /// changelog and for the LICENSE copyright years [default: the date of
+    /// `$SOURCE_DATE_EPOCH` if set, or else today]
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    date: Option<NaiveDate>,
+
+    /// Release every package in the workspace that has changed since its
+    /// latest release tag
+    #[arg(long, conflicts_with_all = ["package", "version"])]
+    changed: bool,
+
+    /// Release the package with the given name in the workspace.
+    ///
+    /// This option can be given multiple times in order to release multiple
+    /// packages; they will be released in dependency order.
+    ///
+    /// By default, the package for the current directory is released.
+    #[arg(short, long, value_name = "NAME")]
+    package: Vec<String>,
+
+    /// Create the GitHub release as a draft, to be published later with
+    /// `rsrepo publish-release`
+    #[arg(long)]
+    draft: bool,
+
+    /// Treat the current branch as a maintenance branch for an older release
+    /// line even if its name does not look like one (e.g., "1.4.x")
+    #[arg(long)]
+    maintenance: bool,
+
+    /// Use the given text as the release commit message instead of opening
+    /// an editor
+    #[arg(short, long, value_name = "TEXT", conflicts_with_all = ["no_edit", "summary"])]
+    message: Option<String>,
+
+    /// Do not open an editor for the release commit message; instead, use a
+    /// message built from the package name, version, and changelog section
+    #[arg(long)]
+    no_edit: bool,
+
+    /// Stop after tagging (or, with `--publish`, after publishing) instead of
+    /// pushing; the release can then be finished with `rsrepo release --push`
+    #[arg(long)]
+    no_push: bool,
+
+    /// Print a description of what would be done without actually doing so
+    #[arg(long)]
+    plan_only: bool,
+
+    /// With `--no-push`, publish the package before stopping
+    #[arg(long, requires = "no_push")]
+    publish: bool,
+
+    /// Finish a release that was stopped before pushing by `--no-push`
+    #[arg(
+        long,
+        conflicts_with_all = ["artifacts", "bump", "changed", "date", "draft", "maintenance", "message", "no_edit", "no_push", "package", "plan_only", "resume", "rich_notes", "sign", "sign_commit", "summary", "verify_package", "version"]
+    )]
+    push: bool,
+
+    /// Add a link comparing the release to the previous release and a list
+    /// of contributors to the GitHub release notes, and link issue
+    /// references in them
+    #[arg(long)]
+    rich_notes: bool,
+
+    /// How to sign the release tag [default: the `release.sign` setting in
+    /// the configuration file, or else "git", which signs using the format
+    /// set by Git's `gpg.format` option]
+    #[arg(long, value_enum, value_name = "git|gpg|ssh|none")]
+    sign: Option<SignMethod>,
+
+    /// Also sign the release commit using the signing method for the tag
+    #[arg(long)]
+    sign_commit: bool,
+
+    /// Use the given text as the short description in the release commit
+    /// message's subject line, and do not open an editor
+    #[arg(long, value_name = "TEXT")]
+    summary: Option<String>,
+
+    /// Do not run the preflight checks before releasing: that the working
+    /// tree is clean, that the default branch (or, for a maintenance release,
+    /// the maintenance branch) is checked out and in sync with the package's
+    /// Git remote (see --remote), that CI passed for HEAD, and that the
+    /// manifests of packages to publish pass `rsrepo lint-manifest`
+    #[arg(long)]
+    skip_checks: bool,
+
+    /// Before committing, run a full `cargo package` (which builds the
+    /// packaged crate) instead of only listing the files to be packaged
+    #[arg(long)]
+    verify_package: bool,
+
+    /// Resume a release that was interrupted partway through, skipping the
+    /// steps that were already completed
+    #[arg(
+        long,
+        conflicts_with_all = ["artifacts", "bump", "changed", "date", "draft", "maintenance", "message", "no_edit", "no_push", "package", "plan_only", "rich_notes", "sign", "sign_commit", "summary", "verify_package", "version"]
+    )]
+    resume: bool,
+
+    /// The version to release.  If neither this argument nor a bump option is
+    /// specified, the Cargo.toml version is used without a prerelease or
+    /// metadata.
+    #[arg(value_parser = parse_v_version, conflicts_with = "bump")]
+    version: Option<Version>,
+}
+
+impl Release {
+    pub(crate) fn run(self, provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        let releaser = Releaser::new(project, provider)?;
+        let journal_file = releaser.journal_file()?;
+        if self.resume {
+            let Some(journal) = journal_file.load()? else {
+                bail!("No interrupted release to resume");
+            };
+            log::info!("Resuming release of {} ...", journal.describe());
+            releaser.execute(journal)?;
+        } else if self.push {
+            let Some(mut journal) = journal_file.load()? else {
+                bail!("No release waiting to be pushed");
+            };
+            if journal.hold.is_none() {
+                bail!(
+                    "The release in progress was not stopped before pushing; run `rsrepo release --resume` to finish it"
+                );
+            }
+            log::info!("Finishing release of {} ...", journal.describe());
+            journal.hold = None;
+            releaser.execute(journal)?;
+        } else {
+            let packages = releaser.select_packages(&self.package, self.changed)?;
+            if packages.len() > 1 && self.version.is_some() {
+                bail!("An explicit version cannot be given when releasing multiple packages");
+            }
+            if packages.len() > 1 && self.message.is_some() {
+                bail!("--message cannot be given when releasing multiple packages");
+            }
+            let config = &releaser.provider.project_config()?.release;
+            let signing = Signing::new(
+                self.sign.unwrap_or(config.sign),
+                self.sign_commit || config.sign_commit,
+            );
+            let plans = packages
+                .into_iter()
+                .map(|p| releaser.plan(p, &self, signing))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if self.plan_only {
+                println!("{}", serde_json::to_string_pretty(&plans)?);
+            } else if let Some(journal) = journal_file.load()? {
+                if journal.hold == Some(Hold::AfterTag) {
+                    bail!(
+                        "A previous release is waiting to be pushed; run `rsrepo release --push` to finish it or `rsrepo unrelease` to discard it"
+                    );
+                } else if journal.hold.is_some() {
+                    bail!(
+                        "A previous release is waiting to be pushed; run `rsrepo release --push` to finish it"
+                    );
+                } else {
+                    bail!(
+                        "A previous release was interrupted; run `rsrepo release --resume` to finish it"
+                    );
+                }
+            } else {
+                if !self.skip_checks {
+                    releaser.preflight(&plans)?;
+                }
+                let hold = match (self.no_push, self.publish) {
+                    (false, _) => None,
+                    (true, false) => Some(Hold::AfterTag),
+                    (true, true) => Some(Hold::AfterPublish),
+                };
+                releaser.execute(Journal::new(plans).hold(hold))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Releaser {
+    project: Project,
+    pkgset: PackageSet,
+    provider: Provider,
+}
+
+impl Releaser {
+    pub(super) fn new(project: Project, provider: Provider) -> anyhow::Result<Releaser> {
+        let pkgset = project.package_set()?;
+        Ok(Releaser {
+            project,
+            pkgset,
+            provider,
+        })
+    }
+
+    /// Returns the prefix (if any) to use for the names of the package's
+    /// release tags
+    fn tag_prefix(&self, package: &Package) -> Option<String> {
+        self.project.tag_prefix(&self.pkgset, package)
+    }
+
+    /// Determine the packages to release, in the order in which they should
+    /// be released
+    fn select_packages(&self, names: &[String], changed: bool) -> anyhow::Result<Vec<&Package>> {
+        if changed {
+            let git = self.project.git();
+            let toplevel = git
+                .toplevel()
+                .context("Could not determine root of Git repository")?;
+            let mut packages = Vec::new();
+            for package in self.pkgset.dependency_order()? {
+                if !package.is_public() {
+                    log::debug!("{} is not published; skipping", package.name());
+                    continue;
+                }
+                let tag_prefix = self.tag_prefix(package);
+                let Some(tag) = git.latest_tag(tag_prefix.as_deref())? else {
+                    log::debug!("{} has never been released", package.name());
+                    packages.push(package);
+                    continue;
+                };
+                // Don't count changes to packages nested inside this one
+                let mut pathspecs = vec![package_pathspec(&toplevel, package, false)?];
+                for p in &self.pkgset {
+                    if p.name() != package.name() && p.path().starts_with(package.path()) {
+                        pathspecs.push(package_pathspec(&toplevel, p, true)?);
+                    }
+                }
+                if git.has_changes_since(&tag, &pathspecs)? {
+                    log::debug!("{} has changed since {tag}", package.name());
+                    packages.push(package);
+                }
+            }
+            if packages.is_empty() {
+                bail!("No packages have changed since their latest release");
+            }
+            Ok(packages)
+        } else if names.is_empty() {
+            Ok(vec![self.pkgset.get(None)?])
+        } else if let [name] = names {
+            Ok(vec![self.pkgset.get(Some(name))?])
+        } else {
+            for name in names {
+                // Error on unknown package names
+                self.pkgset.get(Some(name))?;
+            }
+            Ok(self
+                .pkgset
+                .dependency_order()?
+                .into_iter()
+                .filter(|p| names.iter().any(|n| n == p.name()))
+                .collect())
+        }
+    }
+
+    fn plan(&self, package: &Package, opts: &Release, signing: Signing) -> anyhow::Result<Plan> {
+        let name = package.name();
+        let git = self.project.git();
+        let old_version = &package.metadata().version;
+        let remote = self.provider.remote(Some(package))?;
+        let repository = LocalRepo::new(package.path())
+            .github_remote(&remote)
+            .context("Could not determine GitHub repository for local repository")?;
+        let publish = package.is_public();
+        let registry = publish
+            .then(|| package.publish_registry().map(String::from))
+            .flatten();
+        let on_crates_io = publish && registry.is_none();
+        let Some(default_branch) = git.default_branch()? else {
+            bail!("Could not determine repository's default branch");
+        };
+        let maintenance_branch = match git.current_branch()? {
+            Some(b) if opts.maintenance || (b != default_branch && is_maintenance_branch(&b)) => {
+                Some(b)
+            }
+            Some(_) => None,
+            None if opts.maintenance => {
+                bail!("Cannot do a maintenance release from a detached HEAD")
+            }
+            None => None,
+        };
+        let dev_bump = if maintenance_branch.is_some() {
+            Bump::Patch
+        } else {
+            Bump::Minor
+        };
+
+        let tag_prefix = self.tag_prefix(package);
+        // Determine new version
+        let new_version = if let Some(v) = opts.version.clone() {
+            v // Skips the checks from the other branch
+        } else {
+            opts.bumping
+                .bump(git.latest_tag_version(tag_prefix.as_deref())?, old_version)?
+        };
+        let prefix = tag_prefix.as_deref().unwrap_or_default();
+        for v in ["", "v"] {
+            let tagname = format!("{prefix}{v}{new_version}");
+            if git.tag_exists(&tagname)? {
+                bail!("New version already tagged: {tagname}");
+            }
+        }
+        let tag_name = format!("{prefix}v{new_version}");
+        let previous_tag = git.latest_tag(tag_prefix.as_deref())?;
+
+        let release_date = match opts.date {
+            Some(date) => date,
+            None => today()?,
+        };
+
+        let dependents = package
+            .outdated_dependents(&new_version)
+            .into_iter()
+            .map(|(rname, req)| DependentBump {
+                package: rname.to_owned(),
+                old_req: req.clone(),
+                new_req: new_version.to_string(),
+            })
+            .collect();
+
+        let changelog = if let Some(chlog) = package.changelog().get()? {
+            match chlog.sections.into_iter().next() {
+                Some(ChangelogSection {
+                    header: ChangelogHeader::Released { .. },
+                    ..
+                })
+                | None => bail!("No changelog section to update"),
+                Some(most_recent) => Some(ChangelogUpdate {
+                    old_header: most_recent.header,
+                    new_header: ChangelogHeader::Released {
+                        version: new_version.clone(),
+                        date: release_date,
+                        yanked: false,
+                    },
+                }),
+            }
+        } else {
+            None
+        };
+
+        let commit_message = if let Some(ref msg) = opts.message {
+            CommitMessage::Given(msg.clone())
+        } else if opts.no_edit || opts.summary.is_some() {
+            CommitMessage::Generated {
+                summary: opts.summary.clone(),
+            }
+        } else {
+            CommitMessage::Edit
+        };
+
+        let Some(mut readme) = package.readme().get()? else {
+            bail!("Package lacks README.md");
+        };
+        let activate = new_version.pre.is_empty() && readme.repostatus() == Some(Repostatus::Wip);
+        let readme = ReadmeUpdate {
+            activate,
+            add_crates_links: on_crates_io && readme.ensure_crates_links(name, package.is_lib()),
+            add_changelog_link: readme.ensure_changelog_link(&repository, default_branch),
+        };
+
+        let mut license_years = git.commit_years()?.into_iter().collect::<BTreeSet<_>>();
+        license_years.insert(release_date.year());
+
+        let github_release = !package
+            .path()
+            .join(".github")
+            .join("workflows")
+            .join("release.yml")
+            .exists();
+
+        // Don't let an older release line's patch release take over the
+        // "Latest" badge on GitHub
+        let make_latest = new_version.pre.is_empty()
+            && maintenance_branch.is_none()
+            && git
+                .max_tag_version(tag_prefix.as_deref())?
+                .is_none_or(|v| new_version > v);
+
+        let artifact_targets = if !opts.artifacts {
+            Vec::new()
+        } else if !package.is_bin() {
+            log::warn!("{name} has no binaries; not building release artifacts");
+            Vec::new()
+        } else if !github_release {
+            log::warn!("release.yml workflow exists; not building release artifacts for {name}");
+            Vec::new()
+        } else {
+            let mut targets = vec![artifacts::host_target()?];
+            for t in artifacts::installed_targets()? {
+                if !targets.contains(&t) {
+                    targets.push(t);
+                }
+            }
+            targets
+        };
+
+        let (remove_topics, add_topics) = if activate {
+            (
+                vec![Topic::new("work-in-progress")],
+                on_crates_io
+                    .then(|| Topic::new("available-on-crates-io"))
+                    .into_iter()
+                    .collect(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        package.check_version_files()?;
+
+        let mut hooks = self.provider.project_config()?.release.hooks.clone();
+        hooks.extend(package.config()?.hooks);
+
+        let next_version = package
+            .begin_dev(&self.pkgset)
+            .latest_release(new_version.clone(), release_date)
+            .bump(dev_bump)
+            .next_version();
+
+        Ok(Plan {
+            package: name.to_owned(),
+            repository,
+            remote,
+            default_branch: default_branch.to_owned(),
+            maintenance_branch,
+            old_version: old_version.clone(),
+            new_version,
+            release_date,
+            tag_prefix,
+            tag_name,
+            previous_tag,
+            signing,
+            commit_message,
+            dependents,
+            changelog,
+            readme,
+            license_years,
+            publish,
+            registry,
+            verify_package: opts.verify_package,
+            github_release,
+            draft: opts.draft,
+            make_latest,
+            rich_notes: opts.rich_notes || self.provider.project_config()?.release.rich_notes,
+            artifact_targets,
+            remove_topics,
+            add_topics,
+            hooks,
+            next_version,
+        })
+    }
+
+    fn journal_file(&self) -> anyhow::Result<JournalFile> {
+        JournalFile::for_repo(self.project.git())
+    }
+
+    fn execute(&self, mut journal: Journal) -> anyhow::Result<()> {
+        // Fail early if we don't have a GitHub token and will need one after
+        // pushing.  Releases held before pushing don't touch GitHub until
+        // they're finished with `--push`.
+        if journal.hold.is_none() {
+            self.provider.github()?;
+        }
+        for entry in &journal.entries {
+            if !entry.is_done(Step::Tag) {
+                self.check_signing(&entry.plan)?;
+            }
+        }
+        let journal_file = self.journal_file()?;
+        journal_file.save(&journal)?;
+        for i in 0..journal.entries.len() {
+            if journal.entries.len() > 1 {
+                let plan = &journal.entries[i].plan;
+                log::info!("Releasing {} {} ...", plan.package, plan.new_version);
+            }
+            if !journal.entries[i].is_started() {
+                journal.entries[i].clean_start = !self.project.git().is_dirty()?;
+            }
+            for step in Step::PRE_PUSH {
+                if matches!(step, Step::Publish | Step::PostPublish)
+                    && journal.hold == Some(Hold::AfterTag)
+                {
+                    continue;
+                }
+                self.run_step(&mut journal, &journal_file, i, step)?;
+            }
+        }
+        if journal.hold.is_some() {
+            log::info!(
+                "Release of {} is ready to be pushed; run `rsrepo release --push` to finish it",
+                journal.describe()
+            );
+            return Ok(());
+        }
+        if !journal
+            .entries
+            .iter()
+            .all(|entry| entry.is_done(Step::Push))
+        {
+            if let Err(e) = self.push(&journal) {
+                let last = journal.entries.len() - 1;
+                self.abort(&mut journal, &journal_file, last)?;
+                return Err(e);
+            }
+            for entry in &mut journal.entries {
+                entry.mark_done(Step::Push);
+            }
+            journal_file.save(&journal)?;
+        }
+        for i in 0..journal.entries.len() {
+            for step in Step::POST_PUSH {
+                self.run_step(&mut journal, &journal_file, i, step)?;
+            }
+        }
+        journal_file.remove()?;
+        Ok(())
+    }
+
+    /// Perform `step` for the `i`-th package in the journal, if it has not
+    /// already been done, and record its completion
+    fn run_step(
+        &self,
+        journal: &mut Journal,
+        journal_file: &JournalFile,
+        i: usize,
+        step: Step,
+    ) -> anyhow::Result<()> {
+        let entry = &journal.entries[i];
+        if entry.is_done(step) {
+            log::debug!(
+                "Step {step:?} for {} already completed; skipping",
+                entry.plan.package
+            );
+            return Ok(());
+        }
+        let plan = &entry.plan;
+        let r = match step {
+            Step::Prepare => self.prepare(plan),
+            Step::CheckPackage => self.check_package(plan),
+            Step::Commit => self.commit(plan),
+            Step::Tag => self.tag(plan),
+            Step::VerifyTag => self.verify_tag(plan),
+            Step::Publish => self.publish(plan),
+            // Run as a separate step so that a failing hook doesn't cause an
+            // already-published release to be rolled back
+            Step::PostPublish if plan.publish => self.run_hooks(plan, Hook::PostPublish),
+            Step::PostPublish => Ok(()),
+            Step::Push => self.push(journal),
+            Step::GitHubRelease => self.create_github_release(plan),
+            Step::UploadArtifacts => self.upload_artifacts(plan),
+            Step::Topics => self.update_topics(plan),
+            Step::BeginDev => self.begin_dev(plan),
+            Step::PostRelease => self.run_hooks(plan, Hook::PostRelease),
+        };
+        if let Err(e) = r {
+            self.abort(journal, journal_file, i)?;
+            return Err(e);
+        }
+        let entry = &mut journal.entries[i];
+        entry.mark_done(step);
+        if step == Step::Commit {
+            entry.release_commit = Some(self.project.git().rev_parse("HEAD")?);
+        }
+        journal_file.save(journal)
+    }
+
+    /// Called when a step fails for the `failed`-th package in the journal.
+    /// Roll back as many of the release commits & tags as can be safely
+    /// discarded, starting with the most recent, and remove their packages
+    /// from the journal along with those of any packages whose release was
+    /// never started, reporting all such packages to the user.
+    fn abort(
+        &self,
+        journal: &mut Journal,
+        journal_file: &JournalFile,
+        failed: usize,
+    ) -> anyhow::Result<()> {
+        let mut keep = failed + 1;
+        while keep > 0 {
+            let entry = &journal.entries[keep - 1];
+            if !entry.can_roll_back() {
+                break;
+            }
+            log::warn!(
+                "Release failed; rolling back release of {} ...",
+                entry.plan.package
+            );
+            if let Err(e) = self.rollback(entry) {
+                log::warn!("Rollback failed: {e:?}");
+                break;
+            }
+            keep -= 1;
+        }
+        if keep <= failed {
+            let dropped = Journal {
+                entries: journal.entries.split_off(keep),
+                hold: journal.hold,
+            };
+            log::warn!(
+                "The following packages were not released: {}",
+                dropped.describe()
+            );
+            if keep == 0 {
+                journal_file.remove()?;
+            } else {
+                journal_file.save(journal)?;
+                log::warn!(
+                    "Run `rsrepo release --resume` to finish releasing {}",
+                    journal.describe()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Undo the most recent release of a package, provided that nothing has
+    /// been pushed or published yet.  If there is an interrupted release in
+    /// progress, that release is the one undone; if it covers multiple
+    /// packages, all of them are undone unless `package` is given, in which
+    /// case it must be the last package in the release.
+    pub(super) fn unrelease(&self, package: Option<&str>) -> anyhow::Result<()> {
+        let journal_file = self.journal_file()?;
+        if let Some(mut journal) = journal_file.load()? {
+            while let Some(entry) = journal.entries.last() {
+                if let Some(name) = package
+                    && name != entry.plan.package
+                {
+                    bail!(
+                        "{name} is not the most recent package in the interrupted release of {}; cannot unrelease",
+                        journal.describe()
+                    );
+                }
+                if !entry.is_started() {
+                    // Nothing to undo
+                } else if !entry.is_done(Step::Commit) {
+                    log::warn!(
+                        "Release commit for {} was never made; leaving changes to working tree in place",
+                        entry.plan.package
+                    );
+                } else if entry.is_done(Step::Push) {
+                    bail!("Release has already been pushed; cannot unrelease");
+                } else if !entry.can_roll_back() {
+                    bail!(
+                        "{} {} has already been published; cannot unrelease",
+                        entry.plan.package,
+                        entry.plan.new_version
+                    );
+                } else {
+                    log::info!(
+                        "Unreleasing {} {} ...",
+                        entry.plan.package,
+                        entry.plan.new_version
+                    );
+                    self.rollback(entry)?;
+                }
+                journal.entries.pop();
+                if journal.entries.is_empty() {
+                    journal_file.remove()?;
+                } else {
+                    journal_file.save(&journal)?;
+                }
+                if package.is_some() {
+                    break;
+                }
+            }
+        } else {
+            let package = self.pkgset.get(package)?;
+            let git = self.project.git();
+            let tag_prefix = self.tag_prefix(package);
+            let Some(tag) = git.latest_tag(tag_prefix.as_deref())? else {
+                bail!("No release tag found for {}", package.name());
+            };
+            let head = git.rev_parse("HEAD")?;
+            if git.rev_parse(&tag)? != head {
+                bail!("Latest release tag {tag} does not point to HEAD; cannot unrelease");
+            }
+            let remote = self.provider.remote(Some(package))?;
+            self.undo_release(&remote, &head, Some(&tag))?;
+        }
+        Ok(())
+    }
+
+    fn rollback(&self, entry: &JournalEntry) -> anyhow::Result<()> {
+        if !entry.is_done(Step::Commit) {
+            log::info!("Discarding uncommitted release changes ...");
+            self.project.git().run("reset", ["--hard", "HEAD"])?;
+            return Ok(());
+        }
+        let head = self.project.git().rev_parse("HEAD")?;
+        if entry
+            .release_commit
+            .as_ref()
+            .is_some_and(|commit| commit != &head)
+        {
+            bail!("HEAD is no longer the release commit; refusing to roll back");
+        }
+        let tag = entry.is_done(Step::Tag).then_some(&*entry.plan.tag_name);
+        self.undo_release(&entry.plan.remote, &head, tag)
+    }
+
+    /// Delete the tag `tag` (if given) and reset the release commit `commit`
+    /// (which must be `HEAD`), after first confirming that neither has been
+    /// pushed to `remote`
+    fn undo_release(&self, remote: &str, commit: &str, tag: Option<&str>) -> anyhow::Result<()> {
+        let git = self.project.git();
+        let remote_refs = git
+            .remote_refs(remote)
+            .with_context(|| format!("Failed to query refs on remote {remote:?}"))?;
+        if let Some(tag) = tag
+            && remote_refs.iter().any(|rref| rref.is_tag(tag))
+        {
+            bail!("Tag {tag} has already been pushed to {remote}; refusing to roll back");
+        }
+        if git.is_pushed(&remote_refs, commit)? {
+            bail!("Release commit has already been pushed to {remote}; refusing to roll back");
+        }
+        if let Some(tag) = tag {
+            log::info!("Deleting tag {tag} ...");
+            git.run("tag", ["-d", tag])?;
+        }
+        log::info!("Resetting release commit ...");
+        git.run("reset", ["--keep", "HEAD^"])?;
+        Ok(())
+    }
+
+    /// Publish the draft GitHub release for `tag`, or for the package's most
+    /// recent release tag if no tag is given
+    pub(super) fn publish_release(
+        &self,
+        package: Option<&str>,
+        tag: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let package = self.pkgset.get(package)?;
+        let git = self.project.git();
+        let tag_prefix = self.tag_prefix(package);
+        let tag = match tag {
+            Some(t) => t.to_owned(),
+            None => match git.latest_tag(tag_prefix.as_deref())? {
+                Some(t) => t,
+                None => bail!("No release tag found for {}", package.name()),
+            },
+        };
+        let remote = self.provider.remote(Some(package))?;
+        let repository = LocalRepo::new(package.path())
+            .github_remote(&remote)
+            .context("Could not determine GitHub repository for local repository")?;
+        let github = self.provider.github()?;
+        let Some(release) = github.find_release_by_tag(&repository, &tag)? else {
+            bail!("No GitHub release found for {tag}");
+        };
+        if !release.draft {
+            log::info!("GitHub release for {tag} is already published");
+            return Ok(());
+        }
+        let tagv = match tag_prefix.as_deref() {
+            Some(pre) => tag.strip_prefix(pre).unwrap_or(&tag),
+            None => &tag,
+        };
+        let version = tagv
+            .strip_prefix('v')
+            .unwrap_or(tagv)
+            .parse::<Version>()
+            .with_context(|| format!("Failed to parse Git tag {tag:?} as a version"))?;
+        let make_latest = version.pre.is_empty()
+            && git
+                .max_tag_version(tag_prefix.as_deref())?
+                .is_none_or(|v| version >= v);
+        log::info!("Publishing GitHub release for {tag} ...");
+        let release = github.update_release(
+            &release,
+            UpdateRelease::new().draft(false).make_latest(make_latest),
+        )?;
+        log::info!("Published {}", release.html_url);
+        Ok(())
+    }
+
+    fn prepare(&self, plan: &Plan) -> anyhow::Result<()> {
+        let package = self.pkgset.get(Some(&plan.package))?;
+        let new_version = &plan.new_version;
+        log::info!("Preparing version {new_version} ...");
+
+        if new_version != &plan.old_version {
+            log::info!("Setting version in Cargo.toml ...");
+            package.set_version_and_bump_dependents(new_version, &self.pkgset)?;
+        }
+        package.update_version_files(new_version, false)?;
+
+        if let Some(ref update) = plan.changelog {
+            let chlog_file = package.changelog();
+            let Some(mut chlog) = chlog_file.get()? else {
+                bail!("CHANGELOG.md suddenly disappeared!");
+            };
+            log::info!("Updating CHANGELOG.md ...");
+            let Some(most_recent) = chlog.sections.first_mut() else {
+                bail!("No changelog section to update");
+            };
+            most_recent.header = update.new_header.clone();
+            chlog.update_compare_links(
+                &plan.repository.html_url(),
+                new_version,
+                &plan.tag_name,
+                plan.previous_tag.as_deref(),
+            );
+            chlog_file.set(chlog)?;
+        }
+
+        if plan.readme.activate || plan.readme.add_crates_links {
+            let readme_file = package.readme();
+            let Some(mut readme) = readme_file.get()? else {
+                bail!("Package lacks README.md");
+            };
+            if plan.readme.activate {
+                log::info!("Setting repostatus in README.md to Active ...");
+                readme.set_repostatus_badge(Badge {
+                    alt: "Project Status: Active – The project has reached a stable, usable state and is being actively developed.".into(),
+                    url: "https://www.repostatus.org/badges/latest/active.svg".into(),
+                    target: "https://www.repostatus.org/#active".into(),
+                });
+            }
+            if plan.readme.add_crates_links {
+                log::info!("Adding crates.io links to README.md ...");
+                readme.ensure_crates_links(&plan.package, package.is_lib());
+            }
+            readme_file.set(readme)?;
+        }
+
+        log::info!("Updating copyright years in LICENSE ...");
+        package.update_license_years(plan.license_years.iter().copied())?;
+
+        if !plan.hooks.pre_commit.is_empty() {
+            // Files created by the hooks are added to the index so that
+            // they're included in the release commit by `git commit -a`
+            let git = self.project.git();
+            let before = git.untracked_files()?;
+            self.run_hooks(plan, Hook::PreCommit)?;
+            let created = git
+                .untracked_files()?
+                .into_iter()
+                .filter(|p| !before.contains(p))
+                .collect::<Vec<_>>();
+            if !created.is_empty() {
+                let toplevel = git
+                    .toplevel()
+                    .context("Could not determine root of Git repository")?;
+                git.command()
+                    .arg("add")
+                    .arg("--")
+                    .args(created.iter().map(|p| toplevel.join(p)))
+                    .status()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the commands configured for the given hook in the package's
+    /// directory, with the details of the release passed via environment
+    /// variables
+    fn run_hooks(&self, plan: &Plan, hook: Hook) -> anyhow::Result<()> {
+        let commands = plan.hooks.commands(hook);
+        if commands.is_empty() {
+            return Ok(());
+        }
+        let package = self.pkgset.get(Some(&plan.package))?;
+        log::info!("Running {hook} hooks ...");
+        for command in commands {
+            let args = shell_words::split(command)
+                .with_context(|| format!("Failed to parse {hook} hook command {command:?}"))?;
+            let Some((arg0, args)) = args.split_first() else {
+                bail!("Empty {hook} hook command");
+            };
+            LoggedCommand::new(arg0)
+                .args(args)
+                .current_dir(package.path())
+                .env("RSREPO_PACKAGE", &plan.package)
+                .env("RSREPO_VERSION", plan.new_version.to_string())
+                .env("RSREPO_TAG", &plan.tag_name)
+                .status()
+                .with_context(|| format!("{hook} hook failed"))?;
+        }
+        Ok(())
+    }
+
+    fn commit(&self, plan: &Plan) -> anyhow::Result<()> {
+        log::info!("Committing ...");
+        let mut cmd = self.project.git().command();
+        if plan.signing.commit {
+            if let Some(format) = plan.signing.method.gpg_format() {
+                cmd.arg("-c").arg(format!("gpg.format={format}"));
+            }
+            cmd.arg("commit").arg("-S");
+        } else {
+            cmd.arg("commit");
+        }
+        cmd.arg("-a");
+        // Read the release notes now rather than when planning, as releasing
+        // an earlier package may have added entries to the changelog
+        let notes = if plan.changelog.is_some() {
+            self.pkgset
+                .get(Some(&plan.package))?
+                .changelog()
+                .get()?
+                .and_then(|chlog| chlog.sections.into_iter().next())
+                .map(|sect| sect.content)
+        } else {
+            None
+        };
+        let package_name = plan.tag_prefix.is_some().then_some(&*plan.package);
+        // Must outlive the `git commit` command
+        let template;
+        match plan.commit_message {
+            CommitMessage::Given(ref msg) => {
+                cmd.arg("-m").arg(msg);
+            }
+            CommitMessage::Generated { ref summary } => {
+                cmd.arg("-m").arg(commit_message(
+                    package_name,
+                    &plan.new_version,
+                    notes.as_deref(),
+                    summary.as_deref(),
+                ));
+            }
+            CommitMessage::Edit => {
+                let mut tmpfile =
+                    NamedTempFile::new().context("could not create temporary file")?;
+                write_commit_template(
+                    tmpfile.as_file_mut(),
+                    package_name,
+                    &plan.new_version,
+                    notes.as_deref(),
+                )
+                .context("error writing to commit message template")?;
+                template = tmpfile;
+                cmd.arg("-v").arg("--template").arg(template.path());
+            }
+        }
+        cmd.status().context("Commit cancelled; aborting")?;
+        Ok(())
+    }
+
+    fn tag(&self, plan: &Plan) -> anyhow::Result<()> {
+        log::info!("Tagging ...");
+        let mut cmd = self.project.git().command();
+        if plan.signing.method != SignMethod::None {
+            if let Some(format) = plan.signing.method.gpg_format() {
+                cmd.arg("-c").arg(format!("gpg.format={format}"));
+            }
+            cmd.arg("tag").arg("-s");
+        } else {
+            cmd.arg("tag").arg("-a");
+        }
+        cmd.arg("-m")
+            .arg(plan.tag_message())
+            .arg(&plan.tag_name)
+            .status()?;
+        Ok(())
+    }
+
+    /// Check that, if the release tag will be signed with an SSH key, Git is
+    /// configured to verify SSH signatures, as otherwise the tag would fail
+    /// verification after it's created
+    fn check_signing(&self, plan: &Plan) -> anyhow::Result<()> {
+        let git = self.project.git();
+        let ssh = match plan.signing.method {
+            SignMethod::Ssh => true,
+            SignMethod::Git => git.config_get("gpg.format")?.as_deref() == Some("ssh"),
+            SignMethod::Gpg | SignMethod::None => false,
+        };
+        if ssh && git.config_get("gpg.ssh.allowedSignersFile")?.is_none() {
+            bail!(
+                "The release tag for {} would be signed with SSH, but Git's gpg.ssh.allowedSignersFile is not set, so the signature could not be verified",
+                plan.package
+            );
+        }
+        Ok(())
+    }
+
+    /// Check that the signature on a signed tag can be verified, so that a
+    /// misconfigured signer is caught before pushing
+    fn verify_tag(&self, plan: &Plan) -> anyhow::Result<()> {
+        if plan.signing.method == SignMethod::None {
+            return Ok(());
+        }
+        log::info!("Verifying tag signature ...");
+        self.project
+            .git()
+            .command()
+            .arg("tag")
+            .arg("-v")
+            .arg(&plan.tag_name)
+            .status()
+            .context("Failed to verify signature on release tag")?;
+        Ok(())
+    }
+
+    fn publish(&self, plan: &Plan) -> anyhow::Result<()> {
+        // Skip if `publish = false`
+        if !plan.publish {
+            return Ok(());
+        }
+        let package = self.pkgset.get(Some(&plan.package))?;
+        self.run_hooks(plan, Hook::PrePublish)?;
+        match self.provider.project_config()?.release.publish_strategy {
+            PublishStrategy::Stash => self.publish_with_stash(plan, package),
+            PublishStrategy::Worktree => self.publish_from_worktree(plan, package),
+        }
+    }
+
+    /// Run `cargo publish` in the project's working tree after moving any
+    /// untracked files out of the way
+    fn publish_with_stash(&self, plan: &Plan, package: &Package) -> anyhow::Result<()> {
+        let git = self.project.git();
+        let toplevel = git
+            .toplevel()
+            .context("Could not determine root of Git repository")?;
+        let stash_name = match toplevel.file_name() {
+            Some(s) => format!("{}.stash", s.to_string_lossy()),
+            None => bail!("Cannot calculate sibling directory of repository root"),
+        };
+        let mut stash_dir = toplevel.clone();
+        stash_dir.set_file_name(stash_name);
+        let untracked = git.untracked_files()?;
+        if !untracked.is_empty() {
+            log::info!("Moving untracked files to {} ...", stash_dir.display());
+            for path in untracked {
+                let src = toplevel.join(&path);
+                let dest = stash_dir.join(&path);
+                if let Some(p) = dest.parent() {
+                    fs_err::create_dir_all(p)?;
+                }
+                log::debug!("Moving {src:?} to {dest:?}");
+                rename_exclusive(&src, &dest)
+                    .with_context(|| format!("Failed to move {src:?} to {dest:?}"))?;
+            }
+        }
+
+        let r = plan
+            .cargo_publish(package.manifest_path())
+            .status()
+            .map_err(anyhow::Error::from);
+
+        // Once the package is published, the release must not be rolled back,
+        // so failures to clean up are only reported as warnings.
+        if stash_dir.exists() {
+            log::info!(
+                "Moving untracked files back from {} ...",
+                stash_dir.display()
+            );
+            if let Err(e) = move_dirtree_into(&stash_dir, &toplevel) {
+                log::warn!(
+                    "Failed to move untracked files back from {}: {e:?}",
+                    stash_dir.display()
+                );
+            }
+        }
+
+        r
+    }
+
+    /// Run `cargo publish` in a temporary Git worktree in which the release
+    /// tag is checked out
+    fn publish_from_worktree(&self, plan: &Plan, package: &Package) -> anyhow::Result<()> {
+        let git = self.project.git();
+        let toplevel = git
+            .toplevel()
+            .context("Could not determine root of Git repository")?;
+        let relpath = package
+            .manifest_path()
+            .strip_prefix(&toplevel)
+            .with_context(|| format!("Package {} is not inside Git repository", plan.package))?;
+        let tmpdir = tempfile::Builder::new()
+            .prefix("rsrepo-publish-")
+            .tempdir()
+            .context("could not create temporary directory")?;
+        let worktree = tmpdir.path().join("worktree");
+        log::info!(
+            "Checking out {} in temporary worktree at {} ...",
+            plan.tag_name,
+            worktree.display()
+        );
+        git.command()
+            .arg("worktree")
+            .arg("add")
+            .arg("--detach")
+            .arg(&worktree)
+            .arg(&plan.tag_name)
+            .status()?;
+
+        let r = plan
+            .cargo_publish(&worktree.join(relpath))
+            .status()
+            .map_err(anyhow::Error::from);
+
+        // As with stashing, failure to clean up must not cause a published
+        // release to be rolled back.
+        log::info!("Removing temporary worktree ...");
+        if let Err(e) = git
+            .command()
+            .arg("worktree")
+            .arg("remove")
+            .arg("--force")
+            .arg(&worktree)
+            .status()
+        {
+            log::warn!("Failed to remove temporary worktree: {e:?}");
+        }
+        r
+    }
+
+    /// Push the release branch and the release tags of all packages in the
+    /// journal to their remotes
+    fn push(&self, journal: &Journal) -> anyhow::Result<()> {
+        // Never push some other branch's HEAD onto the release branch (which
+        // could otherwise happen if the preflight checks were skipped)
+        let current = self.project.git().current_branch()?;
+        let mut refspecs: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for entry in &journal.entries {
+            let plan = &entry.plan;
+            match current.as_deref() {
+                Some(b) if b == plan.release_branch() => (),
+                Some(b) => bail!(
+                    "Current branch {b:?} is not the release branch {:?}; refusing to push",
+                    plan.release_branch()
+                ),
+                None => bail!("HEAD is detached; refusing to push"),
+            }
+            let specs = refspecs.entry(&plan.remote).or_default();
+            let branch = format!("HEAD:refs/heads/{}", plan.release_branch());
+            if !specs.contains(&branch) {
+                specs.push(branch);
+            }
+            specs.push(format!("refs/tags/{0}:refs/tags/{0}", plan.tag_name));
+        }
+        for (remote, specs) in refspecs {
+            log::info!("Pushing to {remote} ...");
+            self.project
+                .git()
+                .command()
+                .arg("push")
+                .arg(remote)
+                .args(specs)
+                .status()?;
+        }
+        Ok(())
+    }
+
+    fn create_github_release(&self, plan: &Plan) -> anyhow::Result<()> {
+        if !plan.github_release {
+            log::info!("release.yml workflow exists; expecting it to create GitHub release");
+            return Ok(());
+        }
+        log::info!("Creating GitHub release ...");
+        let text = self
+            .project
+            .git()
+            .command()
+            .arg("show")
+            .arg("-s")
+            .arg("--format=%s%x00%b")
+            .arg(format!("{}^{{commit}}", plan.tag_name))
+            .check_output()?;
+        let (subject, body) = text.split_once('\0').ok_or_else(|| {
+            anyhow::anyhow!("`git show` was asked to output a NUL, but it didn't!")
+        })?;
+        let body = if plan.rich_notes {
+            self.rich_notes(plan, body.trim())?
+        } else {
+            body.trim().to_owned()
+        };
+        let release_details = CreateRelease::new(&plan.tag_name)
+            .name(subject)
+            .body(body)
+            .prerelease(!plan.new_version.pre.is_empty())
+            .draft(plan.draft)
+            .make_latest(plan.make_latest);
+        let release = self
+            .provider
+            .github()?
+            .create_release(&plan.repository, release_details)?;
+        if plan.draft {
+            log::info!(
+                "Draft release created at {}; run `rsrepo publish-release {}` to publish it",
+                release.html_url,
+                plan.tag_name
+            );
+        }
+        Ok(())
+    }
+
+    /// Build the package's binaries for each of the planned targets, package
+    /// them into tarballs, and upload the tarballs and their checksums to the
+    /// GitHub release.  Assets already attached to the release (e.g., by an
+    /// earlier attempt) are not uploaded again.
+    fn upload_artifacts(&self, plan: &Plan) -> anyhow::Result<()> {
+        if plan.artifact_targets.is_empty() {
+            return Ok(());
+        }
+        let package = self.pkgset.get(Some(&plan.package))?;
+        let github = self.provider.github()?;
+        let Some(release) = github.find_release_by_tag(&plan.repository, &plan.tag_name)? else {
+            bail!("No GitHub release found for {}", plan.tag_name);
+        };
+        let outdir = tempfile::Builder::new()
+            .prefix("rsrepo-artifacts-")
+            .tempdir()
+            .context("could not create temporary directory")?;
+        for target in &plan.artifact_targets {
+            for binary in artifacts::build(package, target)? {
+                let archive =
+                    artifacts::archive(outdir.path(), package, &binary, &plan.new_version, target)?;
+                let checksum = artifacts::write_checksum(&archive)?;
+                for (path, content_type) in
+                    [(archive, "application/gzip"), (checksum, "text/plain")]
+                {
+                    let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+                        bail!("Artifact path {} is not valid UTF-8", path.display());
+                    };
+                    if release.assets.iter().any(|asset| asset.name == name) {
+                        log::info!("{name} is already attached to GitHub release; skipping");
+                        continue;
+                    }
+                    log::info!("Uploading {name} to GitHub release ...");
+                    let data = fs_err::read(&path)?;
+                    github.upload_release_asset(&release, name, content_type, &data)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn update_topics(&self, plan: &Plan) -> anyhow::Result<()> {
+        if plan.remove_topics.is_empty() && plan.add_topics.is_empty() {
+            return Ok(());
+        }
+        let github = self.provider.github()?;
+        let mut topics = github
+            .get_topics(&plan.repository)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut changed = false;
+        for tp in &plan.remove_topics {
+            if topics.remove(tp) {
+                changed = true;
+            }
+        }
+        for tp in &plan.add_topics {
+            if topics.insert(tp.clone()) {
+                changed = true;
+            }
+        }
+        if changed {
+            log::info!("Updating GitHub repository topics ...");
+            github.set_topics(&plan.repository, topics)?;
+        }
+        Ok(())
+    }
+
+    fn begin_dev(&self, plan: &Plan) -> anyhow::Result<()> {
+        let package = self.pkgset.get(Some(&plan.package))?;
+        package
+            .begin_dev(&self.pkgset)
+            .latest_release(plan.new_version.clone(), plan.release_date)
+            .bump(plan.dev_bump())
+            // Without `force()`, begin-dev won't be run, because it does
+            // nothing if `package.metadata.version` is already a dev version,
+            // but the field isn't updated when `version` in `Cargo.toml` is
+            // updated, and so it remains at its original value from the start
+            // of command execution.
+            .force(true)
+            .run()?;
+
+        // Ensure "Changelog" link is in README
+        if plan.readme.add_changelog_link {
+            let readme_file = package.readme();
+            let Some(mut readme) = readme_file.get()? else {
+                bail!("README.md suddenly disappeared!");
+            };
+            if readme.ensure_changelog_link(&plan.repository, &plan.default_branch) {
+                log::info!("Adding Changelog link to README.md ...");
+                readme_file.set(readme)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A description of the actions that `rsrepo release` will take
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct Plan {
+    package: String,
+    repository: GHRepo,
+    /// The Git remote for the GitHub repository, to which the release is
+    /// pushed
+    remote: String,
+    default_branch: String,
+    /// The maintenance branch for an older release line that the release is
+    /// being made from, if any
+    maintenance_branch: Option<String>,
+    old_version: Version,
+    new_version: Version,
+    release_date: NaiveDate,
+    /// The prefix for the Git tag name, if the package is part of a workspace
+    /// with multiple public packages
+    tag_prefix: Option<String>,
+    tag_name: String,
+    /// The most recent release tag for the package before this release, if
+    /// any
+    previous_tag: Option<String>,
+    signing: Signing,
+    commit_message: CommitMessage,
+    /// Dependent packages in the workspace whose requirements on the package
+    /// will be updated
+    dependents: Vec<DependentBump>,
+    /// How the topmost section header of CHANGELOG.md will be changed, if the
+    /// file exists
+    changelog: Option<ChangelogUpdate>,
+    readme: ReadmeUpdate,
+    /// The years that will be ensured to be present in the LICENSE copyright
+    /// line
+    license_years: BTreeSet<i32>,
+    /// Whether `cargo publish` will be run
+    publish: bool,
+    /// The name of the registry to publish to, if not crates.io
+    registry: Option<String>,
+    /// Whether to run a full `cargo package` before committing rather than
+    /// just `cargo package --list`
+    verify_package: bool,
+    /// Whether a GitHub release will be created (as opposed to leaving it to
+    /// a `release.yml` workflow)
+    github_release: bool,
+    /// Whether the GitHub release will be created as a draft
+    draft: bool,
+    /// Whether the GitHub release will be marked as the repository's latest
+    /// release
+    make_latest: bool,
+    /// Whether to add a compare link and list of contributors to the GitHub
+    /// release notes and to link issue references
+    rich_notes: bool,
+    /// The targets for which to build binaries to attach to the GitHub
+    /// release
+    artifact_targets: Vec<String>,
+    remove_topics: Vec<Topic>,
+    add_topics: Vec<Topic>,
+    /// User-defined commands to run at various points during the release,
+    /// combining those from the configuration file with those from the
+    /// package's metadata
+    hooks: Hooks,
+    /// The version that development will begin on after releasing
+    next_version: Version,
+}
+
+impl Plan {
+    /// Construct a `cargo package` command for the package with the given
+    /// manifest path
+    fn cargo_package(&self, manifest_path: &Path) -> LoggedCommand {
+        let mut cmd = LoggedCommand::new("cargo");
+        // The release changes haven't been committed yet
+        cmd.arg("package")
+            .arg("--allow-dirty")
+            .arg("--manifest-path")
+            .arg(manifest_path);
+        if let Some(ref registry) = self.registry {
+            cmd.arg("--registry").arg(registry);
+        }
+        cmd
+    }
+
+    /// Construct a `cargo publish` command for publishing the package with
+    /// the given manifest path to the planned registry
+    fn cargo_publish(&self, manifest_path: &Path) -> LoggedCommand {
+        match self.registry {
+            Some(ref registry) => log::info!("Publishing to {registry} registry ..."),
+            None => log::info!("Publishing ..."),
+        }
+        let mut cmd = LoggedCommand::new("cargo");
+        cmd.arg("publish").arg("--manifest-path").arg(manifest_path);
+        if let Some(ref registry) = self.registry {
+            cmd.arg("--registry").arg(registry);
+        }
+        cmd
+    }
+
+    /// Returns the branch that the release is made from
+    fn release_branch(&self) -> &str {
+        self.maintenance_branch
+            .as_deref()
+            .unwrap_or(&self.default_branch)
+    }
+
+    /// Returns the level at which to bump the released version when
+    /// beginning development afterwards
+    fn dev_bump(&self) -> Bump {
+        if self.maintenance_branch.is_some() {
+            Bump::Patch
+        } else {
+            Bump::Minor
+        }
+    }
+
+    fn tag_message(&self) -> String {
+        if self.tag_prefix.is_some() {
+            format!("{} version {}", self.package, self.new_version)
+        } else {
+            format!("Version {}", self.new_version)
+        }
+    }
+}
+
+/// How the release commit message will be composed
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum CommitMessage {
+    /// Use the given message as-is
+    Given(String),
+    /// Generate the message from the package version, the given summary, and
+    /// the release notes in the changelog at the time of committing
+    Generated { summary: Option<String> },
+    /// Compose the message in an editor, starting from a template
+    Edit,
+}
+
+/// How the release tag & commit will be signed
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct Signing {
+    method: SignMethod,
+    /// Whether the release commit will be signed as well
+    commit: bool,
+}
+
+impl Signing {
+    fn new(method: SignMethod, commit: bool) -> Signing {
+        Signing {
+            method,
+            commit: commit && method != SignMethod::None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct DependentBump {
+    package: String,
+    old_req: VersionReq,
+    new_req: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct ChangelogUpdate {
+    old_header: ChangelogHeader,
+    new_header: ChangelogHeader,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct ReadmeUpdate {
+    /// Whether the repostatus badge will be changed from "WIP" to "Active"
+    activate: bool,
+    add_crates_links: bool,
+    add_changelog_link: bool,
+}
+
+#[derive(Args, Clone, Debug, Default, Eq, PartialEq)]
+#[group(multiple = true, id = "bump")]
+pub(crate) struct Bumping {
+    /// Release the final version of the latest prerelease tag
+    #[arg(long, conflicts_with_all = ["major", "minor", "patch", "pre"])]
+    finalize: bool,
+
+    /// Release the next major version
+    #[arg(long, conflicts_with_all = ["minor", "patch"])]
+    major: bool,
+
+    /// Release the next minor version
+    #[arg(long, conflicts_with = "patch")]
+    minor: bool,
+
+    /// Release the next patch version
+    #[arg(long)]
+    patch: bool,
+
+    /// Release the next prerelease at the given level.  When combined with
+    /// --major, --minor, or --patch, the first such prerelease of the bumped
+    /// version is released.
+    #[arg(long, value_enum, value_name = "alpha|beta|rc")]
+    pre: Option<PreLevel>,
+}
+
+impl Bumping {
+    fn bump(
+        &self,
+        tag_version: Option<Version>,
+        manifest_version: &Version,
+    ) -> anyhow::Result<Version> {
+        if self.finalize {
+            let Some(tag_version) = tag_version else {
+                bail!("No Git tag to finalize");
+            };
+            if tag_version.pre.is_empty() {
+                bail!("Latest Git tag is not a prerelease; cannot finalize");
+            }
+            Ok(Version::new(
+                tag_version.major,
+                tag_version.minor,
+                tag_version.patch,
+            ))
+        } else if let Some(pre) = self.pre {
+            let manifest_release = Version::new(
+                manifest_version.major,
+                manifest_version.minor,
+                manifest_version.patch,
+            );
+            match tag_version {
+                // If development has begun on a version beyond the next one
+                // after the tag (e.g., via `begin-dev --major`), start a
+                // prerelease series for the manifest version instead
+                Some(tag_version)
+                    if self.level().is_none() && manifest_release > next_release(&tag_version) =>
+                {
+                    Ok(pre.version(&manifest_release, 1))
+                }
+                Some(tag_version) => bump_prerelease(&tag_version, self.level(), pre),
+                None if self.level().is_some() => bail!("No Git tag to bump"),
+                // Start a prerelease series for the manifest version
+                None => Ok(pre.version(manifest_version, 1)),
+            }
+        } else if let Some(level) = self.level() {
+            if let Some(tag_version) = tag_version {
+                if !tag_version.pre.is_empty() {
+                    bail!("Latest Git tag is a prerelease; cannot bump");
+                }
+                Ok(bump_version(tag_version, level))
+            } else {
+                bail!("No Git tag to bump");
+            }
+        } else {
+            if tag_version.is_some_and(|v| v >= *manifest_version) {
+                bail!("Latest Git-tagged version exceeds manifest version");
+            }
+            // Strip any pre-release segment
+            Ok(Version::new(
+                manifest_version.major,
+                manifest_version.minor,
+                manifest_version.patch,
+            ))
+        }
+    }
+
+    pub(crate) fn level(&self) -> Option<Bump> {
+        if self.major {
+            Some(Bump::Major)
+        } else if self.minor {
+            Some(Bump::Minor)
+        } else if self.patch {
+            Some(Bump::Patch)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns a Git pathspec, relative to the repository root, matching the files
+/// in `package`'s directory, or excluding them if `exclude` is true
+fn package_pathspec(toplevel: &Path, package: &Package, exclude: bool) -> anyhow::Result<String> {
+    let relpath = package
+        .path()
+        .strip_prefix(toplevel)
+        .with_context(|| format!("Package {} is not inside Git repository", package.name()))?;
+    let magic = if exclude { "top,exclude" } else { "top" };
+    Ok(format!(":({magic}){}", relpath.display()))
+}
+
+/// Returns the final version that bumping `v` to a prerelease without a bump
+/// level would produce a prerelease of
+fn next_release(v: &Version) -> Version {
+    let release = Version::new(v.major, v.minor, v.patch);
+    if v.pre.is_empty() {
+        bump_version(release, Bump::Minor)
+    } else {
+        release
+    }
+}
+
+/// Returns true if `branch` is named like a maintenance branch for a release
+/// line, i.e., if its final path component is of the form `{major}.x` or
+/// `{major}.{minor}.x`
+fn is_maintenance_branch(branch: &str) -> bool {
+    let name = branch.rsplit('/').next().unwrap_or(branch);
+    let Some(line) = name.strip_suffix(".x") else {
+        return false;
+    };
+    let parts = line.split('.').collect::<Vec<_>>();
+    (1..=2).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Construct a release commit message.  The subject consists of the package
+/// name (if given) and version, followed by `summary`; if there are no release
+/// notes (i.e., no changelog), the summary defaults to "Initial release".  The
+/// release notes, if any, form the body.
+fn commit_message(
+    package_name: Option<&str>,
+    version: &Version,
+    notes: Option<&str>,
+    summary: Option<&str>,
+) -> String {
+    let mut subject = match package_name {
+        Some(name) => format!("{name} v{version}"),
+        None => format!("v{version}"),
+    };
+    let summary = if notes.is_some() {
+        summary
+    } else {
+        Some(summary.unwrap_or("Initial release"))
+    };
+    if let Some(summary) = summary {
+        subject.push_str(" — ");
+        subject.push_str(summary);
+    }
+    match notes {
+        Some(notes) => format!("{subject}\n\n{notes}"),
+        None => subject,
+    }
+}
+
+fn write_commit_template<W: Write>(
+    mut fp: W,
+    package_name: Option<&str>,
+    version: &Version,
+    notes: Option<&str>,
+) -> io::Result<()> {
+    writeln!(fp, "DELETE THIS LINE")?;
+    writeln!(fp)?;
+    let summary = notes.is_some().then_some("INSERT SHORT DESCRIPTION HERE");
+    writeln!(
+        fp,
+        "{}",
+        commit_message(package_name, version, notes, summary)
+    )?;
+    writeln!(fp)?;
+    writeln!(fp, "# Write in Markdown.")?;
+    writeln!(fp, "# The first line will be used as the release name.")?;
+    writeln!(fp, "# The rest will be used as the release body.")?;
+    fp.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("1.x", true)]
+    #[case("1.4.x", true)]
+    #[case("release/1.4.x", true)]
+    #[case("fibcore/0.2.x", true)]
+    #[case("main", false)]
+    #[case("1.4.3.x", false)]
+    #[case("v1.4.x", false)]
+    #[case("1..x", false)]
+    #[case(".x", false)]
+    fn test_is_maintenance_branch(#[case] branch: &str, #[case] r: bool) {
+        assert_eq!(is_maintenance_branch(branch), r);
+    }
+
+    #[rstest]
+    #[case(None, None, None, "v1.2.3 — Initial release")]
+    #[case(Some("foo"), None, None, "foo v1.2.3 — Initial release")]
+    #[case(None, None, Some("Big news"), "v1.2.3 — Big news")]
+    #[case(None, Some("- Fixed a bug"), None, "v1.2.3\n\n- Fixed a bug")]
+    #[case(
+        Some("foo"),
+        Some("- Fixed a bug"),
+        Some("Bugfix release"),
+        "foo v1.2.3 — Bugfix release\n\n- Fixed a bug"
+    )]
+    fn build_commit_message(
+        #[case] package_name: Option<&str>,
+        #[case] notes: Option<&str>,
+        #[case] summary: Option<&str>,
+        #[case] msg: &str,
+    ) {
+        let version = Version::new(1, 2, 3);
+        assert_eq!(commit_message(package_name, &version, notes, summary), msg);
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.4", "1.2.4")]
+    #[case("1.2.3", "1.2.4-dev", "1.2.4")]
+    #[case("1.2.3-alpha", "1.2.3-alpha.1", "1.2.3")]
+    fn bumping_default(
+        #[case] tag_version: Version,
+        #[case] manifest_version: Version,
+        #[case] bumped: Version,
+    ) {
+        assert_eq!(
+            Bumping::default()
+                .bump(Some(tag_version), &manifest_version)
+                .unwrap(),
+            bumped
+        );
+    }
+
+    #[rstest]
+    #[case("1.2.4", "1.2.4")]
+    #[case("1.2.4-dev", "1.2.4")]
+    fn bumping_default_no_tag(#[case] manifest_version: Version, #[case] bumped: Version) {
+        assert_eq!(
+            Bumping::default().bump(None, &manifest_version).unwrap(),
+            bumped
+        );
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.3")]
+    #[case("1.2.3", "1.2.0")]
+    #[case("1.2.3", "1.2.3-dev")]
+    #[case("1.2.3", "1.2.2-dev")]
+    #[case("1.2.3-alpha.1", "1.2.3-alpha")]
+    fn bumping_default_err(#[case] tag_version: Version, #[case] manifest_version: Version) {
+        assert!(
+            Bumping::default()
+                .bump(Some(tag_version), &manifest_version)
+                .is_err()
+        );
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.3", "1.3.0")]
+    #[case("1.2.3", "1.2.3-dev", "1.3.0")]
+    #[case("1.2.3", "1.3.0-dev", "1.3.0")]
+    #[case("1.1.5", "1.2.3", "1.2.0")]
+    #[case("1.2.3", "1.1.5", "1.3.0")]
+    fn bumping_minor(
+        #[case] tag_version: Version,
+        #[case] manifest_version: Version,
+        #[case] bumped: Version,
+    ) {
+        let bumping = Bumping {
+            minor: true,
+            ..Bumping::default()
+        };
+        assert_eq!(
+            bumping.bump(Some(tag_version), &manifest_version).unwrap(),
+            bumped,
+        );
+    }
+
+    #[test]
+    fn bumping_minor_pre_tag() {
+        let bumping = Bumping {
+            minor: true,
+            ..Bumping::default()
+        };
+        let tag_version = "1.2.3-dev".parse::<Version>().unwrap();
+        let manifest_version = Version::new(1, 2, 3);
+        assert!(bumping.bump(Some(tag_version), &manifest_version).is_err());
+    }
+
+    #[test]
+    fn bumping_minor_no_tag() {
+        let bumping = Bumping {
+            minor: true,
+            ..Bumping::default()
+        };
+        let manifest_version = Version::new(1, 2, 3);
+        assert!(bumping.bump(None, &manifest_version).is_err());
+    }
+
+    #[rstest]
+    #[case(None, "1.2.0", "1.2.1-dev", "1.3.0-rc.1")]
+    #[case(None, "1.3.0-rc.1", "1.3.0-dev", "1.3.0-rc.2")]
+    #[case(Some(Bump::Major), "1.2.0", "1.3.0-dev", "2.0.0-rc.1")]
+    #[case(None, "1.4.2", "2.0.0-dev", "2.0.0-rc.1")]
+    #[case(None, "1.5.0-beta.1", "2.0.0-dev", "2.0.0-rc.1")]
+    fn bumping_pre(
+        #[case] level: Option<Bump>,
+        #[case] tag_version: Version,
+        #[case] manifest_version: Version,
+        #[case] bumped: Version,
+    ) {
+        let bumping = Bumping {
+            major: level == Some(Bump::Major),
+            pre: Some(PreLevel::Rc),
+            ..Bumping::default()
+        };
+        assert_eq!(
+            bumping.bump(Some(tag_version), &manifest_version).unwrap(),
+            bumped,
+        );
+    }
+
+    #[test]
+    fn bumping_pre_no_tag() {
+        let bumping = Bumping {
+            pre: Some(PreLevel::Alpha),
+            ..Bumping::default()
+        };
+        let manifest_version = "0.1.0-dev".parse::<Version>().unwrap();
+        assert_eq!(
+            bumping.bump(None, &manifest_version).unwrap(),
+            "0.1.0-alpha.1".parse::<Version>().unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(Some("1.3.0-rc.2"), Some("1.3.0"))]
+    #[case(Some("1.3.0"), None)]
+    #[case(None, None)]
+    fn bumping_finalize(#[case] tag_version: Option<&str>, #[case] bumped: Option<&str>) {
+        let bumping = Bumping {
+            finalize: true,
+            ..Bumping::default()
+        };
+        let tag_version = tag_version.map(|v| v.parse::<Version>().unwrap());
+        let manifest_version = "1.3.0-dev".parse::<Version>().unwrap();
+        let r = bumping.bump(tag_version, &manifest_version);
+        match bumped {
+            Some(v) => assert_eq!(r.unwrap(), v.parse::<Version>().unwrap()),
+            None => assert!(r.is_err()),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use super::{Plan, Releaser};
+use ghrepo::GHRepo;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::LazyLock;
+
+/// Matches an issue or pull request reference like "#123" that is not part of
+/// a larger word, URL, HTML entity, or existing link
+static ISSUE_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(^|[^\w&/\[])#(\d+)\b").expect("issue reference regex should be valid")
+});
+
+impl Releaser {
+    /// Compose enriched GitHub release notes for `plan` from `body`, the
+    /// body of the release commit's message
+    pub(super) fn rich_notes(&self, plan: &Plan, body: &str) -> anyhow::Result<String> {
+        let range = match plan.previous_tag {
+            Some(ref prev) => format!("{prev}..{}", plan.tag_name),
+            None => plan.tag_name.clone(),
+        };
+        let authors = self
+            .project
+            .git()
+            .readlines("log", ["--reverse", "--format=%aN%x00%aE", &range])?
+            .filter_map(|ln| {
+                let (name, email) = ln.split_once('\0')?;
+                Some((name.to_owned(), email.to_owned()))
+            })
+            .collect::<Vec<_>>();
+        let handles = &self.provider.project_config()?.release.github_handles;
+        Ok(compose_notes(
+            body,
+            &plan.repository,
+            plan.previous_tag.as_deref(),
+            &plan.tag_name,
+            &contributors(&authors, handles),
+        ))
+    }
+}
+
+/// Returns the display names of the non-bot authors in `authors` (a list of
+/// name & e-mail pairs), in order of first appearance and without duplicates.
+/// Authors whose GitHub handles are known are displayed as "@handle".
+fn contributors(authors: &[(String, String)], handles: &BTreeMap<String, String>) -> Vec<String> {
+    let mut names = Vec::new();
+    for (name, email) in authors {
+        if is_bot(name, email) {
+            continue;
+        }
+        let display = match github_handle(email, handles) {
+            Some(handle) => format!("@{handle}"),
+            None => name.clone(),
+        };
+        if !names.contains(&display) {
+            names.push(display);
+        }
+    }
+    names
+}
+
+fn is_bot(name: &str, email: &str) -> bool {
+    name.ends_with("[bot]")
+        || email
+            .split_once('@')
+            .is_some_and(|(local, _)| local.ends_with("[bot]"))
+}
+
+/// Determine the GitHub handle for an e-mail address, either from the
+/// configured mapping or from a GitHub "noreply" address
+fn github_handle<'a>(email: &'a str, handles: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    if let Some(handle) = handles.get(email) {
+        return Some(handle);
+    }
+    let local = email.strip_suffix("@users.noreply.github.com")?;
+    Some(local.split_once('+').map_or(local, |(_, user)| user))
+}
+
+/// Replace issue & pull request references like "#123" in `text` with
+/// Markdown links to the issue in `repo`
+fn link_issues(text: &str, repo: &GHRepo) -> String {
+    ISSUE_REF
+        .replace_all(text, |caps: &Captures<'_>| {
+            format!(
+                "{}[#{n}]({}/issues/{n})",
+                &caps[1],
+                repo.html_url(),
+                n = &caps[2]
+            )
+        })
+        .into_owned()
+}
+
+fn compose_notes(
+    body: &str,
+    repo: &GHRepo,
+    previous_tag: Option<&str>,
+    tag: &str,
+    contributors: &[String],
+) -> String {
+    let mut notes = link_issues(body, repo);
+    if !contributors.is_empty() {
+        if !notes.is_empty() {
+            notes.push_str("\n\n");
+        }
+        notes.push_str("### Contributors\n");
+        for c in contributors {
+            notes.push_str("\n- ");
+            notes.push_str(c);
+        }
+    }
+    if let Some(prev) = previous_tag {
+        if !notes.is_empty() {
+            notes.push_str("\n\n");
+        }
+        let _ = write!(
+            &mut notes,
+            "**Full Changelog**: {}/compare/{prev}...{tag}",
+            repo.html_url()
+        );
+    }
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "Fixed a bug (#12)",
+        "Fixed a bug ([#12](https://github.com/jwodder/foobar/issues/12))"
+    )]
+    #[case(
+        "#3 and #45.",
+        "[#3](https://github.com/jwodder/foobar/issues/3) and [#45](https://github.com/jwodder/foobar/issues/45)."
+    )]
+    #[case("See [#12](https://example.com)", "See [#12](https://example.com)")]
+    #[case("rust-lang/rust#12", "rust-lang/rust#12")]
+    #[case("&#123;", "&#123;")]
+    #[case("issue#12", "issue#12")]
+    #[case("#abc", "#abc")]
+    fn test_link_issues(#[case] text: &str, #[case] linked: &str) {
+        let repo = GHRepo::new("jwodder", "foobar").unwrap();
+        assert_eq!(link_issues(text, &repo), linked);
+    }
+
+    #[test]
+    fn test_contributors() {
+        let authors = [
+            ("John T. Wodder II", "jwodder@example.com"),
+            (
+                "dependabot[bot]",
+                "49699333+dependabot[bot]@users.noreply.github.com",
+            ),
+            ("Alice", "12345+alice@users.noreply.github.com"),
+            (
+                "pre-commit-ci[bot]",
+                "66853113+pre-commit-ci[bot]@users.noreply.github.com",
+            ),
+            ("Bob", "bob@example.com"),
+            ("John T. Wodder II", "jwodder@example.com"),
+            ("Alice", "alice@users.noreply.github.com"),
+        ]
+        .map(|(name, email)| (name.to_owned(), email.to_owned()));
+        let handles = BTreeMap::from([("jwodder@example.com".to_owned(), "jwodder".to_owned())]);
+        assert_eq!(
+            contributors(&authors, &handles),
+            ["@jwodder", "@alice", "Bob"]
+        );
+    }
+
+    #[test]
+    fn test_compose_notes() {
+        let repo = GHRepo::new("jwodder", "foobar").unwrap();
+        let notes = compose_notes(
+            "- Fixed a bug (#12)",
+            &repo,
+            Some("v0.1.0"),
+            "v0.2.0",
+            &["@jwodder".into(), "Bob".into()],
+        );
+        assert_eq!(
+            notes,
+            indoc! {"
+                - Fixed a bug ([#12](https://github.com/jwodder/foobar/issues/12))
+
+                ### Contributors
+
+                - @jwodder
+                - Bob
+
+                **Full Changelog**: https://github.com/jwodder/foobar/compare/v0.1.0...v0.2.0"}
+        );
+    }
+
+    #[test]
+    fn test_compose_notes_first_release() {
+        let repo = GHRepo::new("jwodder", "foobar").unwrap();
+        let notes = compose_notes("Initial release", &repo, None, "v0.1.0", &[]);
+        assert_eq!(notes, "Initial release");
+    }
+}
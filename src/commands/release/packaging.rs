@@ -0,0 +1,115 @@
+use super::{Plan, Releaser};
+use crate::git::Git;
+use anyhow::{Context, bail};
+use std::collections::HashSet;
+
+/// The maximum size of a `.crate` file accepted by crates.io, in bytes
+const CRATES_IO_MAX_SIZE: u64 = 10 * 1024 * 1024;
+
+impl Releaser {
+    /// Check that the package can be packaged for publishing: run `cargo
+    /// package --list` and fail if any files that would be included are
+    /// untracked or ignored by Git, then run `cargo package` (with
+    /// `--no-verify` unless a full verification was requested) and check the
+    /// size of the resulting `.crate` file
+    pub(super) fn check_package(&self, plan: &Plan) -> anyhow::Result<()> {
+        // Skip if `publish = false`
+        if !plan.publish {
+            return Ok(());
+        }
+        let package = self.pkgset.get(Some(&plan.package))?;
+        log::info!("Checking package contents ...");
+        let listing = plan
+            .cargo_package(package.manifest_path())
+            .arg("--no-verify")
+            .arg("--list")
+            .check_output()?;
+        let tracked = Git::new(package.path())
+            .readlines("ls-files", ["."])?
+            .collect::<HashSet<_>>();
+        // Files that Cargo generates (like `Cargo.toml.orig`) or takes from
+        // outside the package directory (like a workspace's `Cargo.lock` or a
+        // `readme` in a parent directory) are listed under names that don't
+        // exist in the package directory and so are skipped here.
+        let uncommitted = listing
+            .lines()
+            .filter(|&path| {
+                path != "Cargo.lock"
+                    && !tracked.contains(path)
+                    && package.path().join(path).exists()
+            })
+            .collect::<Vec<_>>();
+        if !uncommitted.is_empty() {
+            for path in &uncommitted {
+                log::error!("{path} would be packaged but is untracked or ignored by Git");
+            }
+            bail!("Package would include files not committed to Git");
+        }
+
+        if plan.verify_package {
+            log::info!("Verifying that the package builds ...");
+        } else {
+            log::info!("Building .crate file ...");
+        }
+        let mut cmd = plan.cargo_package(package.manifest_path());
+        if !plan.verify_package {
+            cmd.arg("--no-verify");
+        }
+        cmd.status()?;
+        let crate_file = self
+            .project
+            .target_directory()?
+            .join("package")
+            .join(format!("{}-{}.crate", plan.package, plan.new_version));
+        let size = fs_err::metadata(&crate_file)
+            .with_context(|| format!("Failed to get size of {}", crate_file.display()))?
+            .len();
+        if plan.registry.is_none() {
+            log::info!(
+                "Package size: {} (crates.io limit: {})",
+                human_size(size),
+                human_size(CRATES_IO_MAX_SIZE)
+            );
+            if size > CRATES_IO_MAX_SIZE {
+                bail!("Package is too large to upload to crates.io");
+            }
+        } else {
+            log::info!("Package size: {}", human_size(size));
+        }
+        Ok(())
+    }
+}
+
+/// Format a size in bytes using binary units
+fn human_size(bytes: u64) -> String {
+    #[expect(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{bytes} B")
+            } else {
+                format!("{size:.1} {unit}")
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.1} GiB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1024, "1.0 KiB")]
+    #[case(1536, "1.5 KiB")]
+    #[case(10 * 1024 * 1024, "10.0 MiB")]
+    #[case(3 * 1024 * 1024 * 1024, "3.0 GiB")]
+    fn test_human_size(#[case] bytes: u64, #[case] s: &str) {
+        assert_eq!(human_size(bytes), s);
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Plan, Releaser};
+use crate::github::CiStatus;
+use anyhow::bail;
+
+impl Releaser {
+    /// Check that the repository is in a fit state for releasing before any
+    /// changes are made
+    pub(super) fn preflight(&self, plans: &[Plan]) -> anyhow::Result<()> {
+        let Some(plan) = plans.first() else {
+            return Ok(());
+        };
+        log::info!("Running preflight checks ...");
+        let git = self.project.git();
+        let mut problems = Vec::new();
+
+        if git.is_dirty()? {
+            problems.push(String::from("Working tree has uncommitted changes"));
+        }
+
+        let branch = plan.release_branch();
+        match git.current_branch()? {
+            Some(b) if b == branch => (),
+            Some(b) if plan.maintenance_branch.is_some() => problems.push(format!(
+                "Currently on branch {b:?} rather than maintenance branch {branch:?}"
+            )),
+            Some(b) => problems.push(format!(
+                "Currently on branch {b:?} rather than default branch {branch:?}"
+            )),
+            None => problems.push(String::from("HEAD is detached")),
+        }
+
+        let remote = &plan.remote;
+        log::info!("Fetching {branch} from {remote} ...");
+        git.run("fetch", [remote, branch])?;
+        let upstream = format!("{remote}/{branch}");
+        let (ahead, behind) = git.ahead_behind(&upstream)?;
+        if ahead > 0 {
+            problems.push(format!(
+                "HEAD is {ahead} commit(s) ahead of {upstream}; push first so that CI can run"
+            ));
+        }
+        if behind > 0 {
+            problems.push(format!("HEAD is {behind} commit(s) behind {upstream}"));
+        }
+
+        let head = git.rev_parse("HEAD")?;
+        match self.provider.github()?.ci_status(&plan.repository, &head)? {
+            CiStatus::Absent => log::warn!("No CI results found for HEAD"),
+            CiStatus::Success => (),
+            CiStatus::Pending(names) => problems.push(format!(
+                "CI has not finished for HEAD: {}",
+                names.join(", ")
+            )),
+            CiStatus::Failure(names) => {
+                problems.push(format!("CI failed for HEAD: {}", names.join(", ")));
+            }
+        }
+
+        for plan in plans.iter().filter(|plan| plan.publish) {
+            let package = self.pkgset.get(Some(&plan.package))?;
+            problems.extend(
+                package
+                    .lint_manifest()
+                    .into_iter()
+                    .map(|p| format!("{}: {p}", plan.package)),
+            );
+        }
+
+        if !problems.is_empty() {
+            for p in &problems {
+                log::error!("{p}");
+            }
+            bail!("Preflight checks failed; pass --skip-checks to release anyway");
+        }
+        Ok(())
+    }
+}
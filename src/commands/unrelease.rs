@@ -0,0 +1,21 @@
+use super::release::Releaser;
+use crate::project::Project;
+use crate::provider::Provider;
+use clap::Args;
+
+/// Undo a release that has not yet been pushed or published
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Unrelease {
+    /// Unrelease the package with the given name in the workspace.
+    ///
+    /// By default, the package for the current directory is unreleased.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+}
+
+impl Unrelease {
+    pub(crate) fn run(self, provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        Releaser::new(project, provider)?.unrelease(self.package.as_deref())
+    }
+}
@@ -0,0 +1,147 @@
+use crate::changelog::ChangelogHeader;
+use crate::cmd::LoggedCommand;
+use crate::github::UpdateRelease;
+use crate::project::{Package, Project};
+use crate::provider::Provider;
+use anyhow::{Context, bail};
+use cargo_metadata::semver::Version;
+use clap::Args;
+use ghrepo::LocalRepo;
+
+/// The prefix added to the titles of GitHub releases for yanked versions
+static YANKED_PREFIX: &str = "[YANKED] ";
+
+/// Yank a release from the package registry and mark it as yanked in the
+/// changelog and on GitHub
+#[derive(Args, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Yank {
+    /// Yank a release of the package with the given name in the workspace.
+    ///
+    /// By default, the package for the current directory is used.
+    #[arg(short, long, value_name = "NAME")]
+    package: Option<String>,
+
+    /// Undo a previous yank
+    #[arg(long)]
+    undo: bool,
+
+    /// The version to yank
+    version: Version,
+}
+
+impl Yank {
+    pub(crate) fn run(self, provider: Provider) -> anyhow::Result<()> {
+        let project = Project::locate()?;
+        let pkgset = project.package_set()?;
+        let package = pkgset.get(self.package.as_deref())?;
+        if !package.is_public() {
+            bail!("Package {} is not published", package.name());
+        }
+
+        self.cargo_yank(package)?;
+
+        let yank = !self.undo;
+        let chlog_file = package.changelog();
+        if let Some(mut chlog) = chlog_file.get()? {
+            let header = chlog
+                .sections
+                .iter_mut()
+                .find_map(|sect| match sect.header {
+                    ChangelogHeader::Released {
+                        ref version,
+                        ref mut yanked,
+                        ..
+                    } if *version == self.version => Some(yanked),
+                    _ => None,
+                });
+            match header {
+                Some(yanked) if *yanked != yank => {
+                    log::info!("Updating CHANGELOG.md ...");
+                    *yanked = yank;
+                    chlog_file.set(chlog)?;
+                    log::info!("Remember to commit the changes to CHANGELOG.md");
+                }
+                Some(_) => log::info!("CHANGELOG.md is already up to date"),
+                None => log::warn!("No CHANGELOG.md section found for {}", self.version),
+            }
+        }
+
+        let tag = format!(
+            "{}v{}",
+            project
+                .tag_prefix(&pkgset, package)
+                .as_deref()
+                .unwrap_or_default(),
+            self.version
+        );
+        let repository = LocalRepo::new(package.path())
+            .github_remote(&provider.remote(Some(package))?)
+            .context("Could not determine GitHub repository for local repository")?;
+        let github = provider.github()?;
+        let Some(release) = github.find_release_by_tag(&repository, &tag)? else {
+            log::warn!("No GitHub release found for {tag}");
+            return Ok(());
+        };
+        let name = if release.name.is_empty() {
+            &tag
+        } else {
+            &release.name
+        };
+        match release_title(name, self.undo) {
+            Some(title) => {
+                log::info!("Updating title of GitHub release for {tag} ...");
+                github.update_release(&release, UpdateRelease::new().name(title))?;
+            }
+            None => log::info!("GitHub release title is already up to date"),
+        }
+        Ok(())
+    }
+
+    fn cargo_yank(&self, package: &Package) -> anyhow::Result<()> {
+        if self.undo {
+            log::info!("Unyanking {} {} ...", package.name(), self.version);
+        } else {
+            log::info!("Yanking {} {} ...", package.name(), self.version);
+        }
+        let mut cmd = LoggedCommand::new("cargo");
+        cmd.arg("yank")
+            .arg("--version")
+            .arg(self.version.to_string());
+        if self.undo {
+            cmd.arg("--undo");
+        }
+        if let Some(registry) = package.publish_registry() {
+            cmd.arg("--registry").arg(registry);
+        }
+        cmd.arg(package.name())
+            .current_dir(package.path())
+            .status()?;
+        Ok(())
+    }
+}
+
+/// Returns the new title for a GitHub release currently titled `name` when
+/// yanking (or, if `undo` is true, unyanking) it, or `None` if the title does
+/// not need to change
+fn release_title(name: &str, undo: bool) -> Option<String> {
+    match (name.strip_prefix(YANKED_PREFIX), undo) {
+        (Some(_), false) | (None, true) => None,
+        (None, false) => Some(format!("{YANKED_PREFIX}{name}")),
+        (Some(orig), true) => Some(orig.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("v1.2.3", false, Some("[YANKED] v1.2.3"))]
+    #[case("[YANKED] v1.2.3", false, None)]
+    #[case("[YANKED] v1.2.3", true, Some("v1.2.3"))]
+    #[case("v1.2.3", true, None)]
+    fn test_release_title(#[case] name: &str, #[case] undo: bool, #[case] title: Option<&str>) {
+        assert_eq!(release_title(name, undo).as_deref(), title);
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, bail};
 use fs_err::read_to_string;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
@@ -13,6 +16,186 @@ pub(crate) struct Config {
     pub(crate) codecov_token: Option<String>,
 }
 
+/// The settings in the configuration file that are used when operating on an
+/// existing project.  Unlike [`Config`], these all have defaults, so they can
+/// be used even if there is no configuration file.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ProjectConfig {
+    /// The name of the Git remote for the GitHub repository
+    pub(crate) remote: Option<String>,
+    #[serde(default)]
+    pub(crate) release: ReleaseConfig,
+}
+
+/// Settings for `rsrepo release`, given in the `[release]` table
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct ReleaseConfig {
+    #[serde(default)]
+    pub(crate) publish_strategy: PublishStrategy,
+    #[serde(default)]
+    pub(crate) sign: SignMethod,
+    #[serde(default)]
+    pub(crate) sign_commit: bool,
+    #[serde(default)]
+    pub(crate) hooks: Hooks,
+    #[serde(default)]
+    pub(crate) rich_notes: bool,
+    /// Mapping from commit author e-mail addresses to GitHub usernames, used
+    /// when listing contributors in GitHub release notes
+    #[serde(default)]
+    pub(crate) github_handles: BTreeMap<String, String>,
+}
+
+/// Settings for a package given in its `[package.metadata.rsrepo]` table
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PackageConfig {
+    #[serde(default)]
+    pub(crate) hooks: Hooks,
+    /// The name of the Git remote for the GitHub repository
+    pub(crate) remote: Option<String>,
+    #[serde(default)]
+    pub(crate) version_files: Vec<VersionFile>,
+}
+
+/// A search & replace operation for updating the version string in a file,
+/// given as an entry in `[[package.metadata.rsrepo.version-files]]`
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct VersionFile {
+    /// The path to the file, relative to the package's directory
+    pub(crate) path: PathBuf,
+    /// A regular expression matching the text to replace
+    pub(crate) search: String,
+    /// The replacement text.  The placeholders `{version}`, `{major}`,
+    /// `{minor}`, and `{patch}` are replaced by the new version and its
+    /// components, and `$N` and `${name}` are replaced by the corresponding
+    /// capture groups of `search`.
+    pub(crate) replace: String,
+    /// Whether to also apply the replacement when beginning development on a
+    /// new version
+    #[serde(default = "default_true")]
+    pub(crate) begin_dev: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Commands to run at various points during `rsrepo release`.  Each hook can
+/// be given as either a single command or a list of commands; each command is
+/// split into arguments using shell-like syntax but is not run via a shell.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct Hooks {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) pre_commit: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) pre_publish: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) post_publish: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) post_release: Vec<String>,
+}
+
+impl Hooks {
+    /// Append the commands in `other` to the commands in `self`
+    pub(crate) fn extend(&mut self, other: Hooks) {
+        self.pre_commit.extend(other.pre_commit);
+        self.pre_publish.extend(other.pre_publish);
+        self.post_publish.extend(other.post_publish);
+        self.post_release.extend(other.post_release);
+    }
+
+    pub(crate) fn commands(&self, hook: Hook) -> &[String] {
+        match hook {
+            Hook::PreCommit => &self.pre_commit,
+            Hook::PrePublish => &self.pre_publish,
+            Hook::PostPublish => &self.post_publish,
+            Hook::PostRelease => &self.post_release,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Hook {
+    PreCommit,
+    PrePublish,
+    PostPublish,
+    PostRelease,
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::PrePublish => "pre-publish",
+            Hook::PostPublish => "post-publish",
+            Hook::PostRelease => "post-release",
+        };
+        f.write_str(name)
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(s) => vec![s],
+        OneOrMany::Many(v) => v,
+    })
+}
+
+/// How `rsrepo release` should run `cargo publish`
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum PublishStrategy {
+    /// Publish from the project's working tree after moving untracked files
+    /// into a sibling directory
+    #[default]
+    Stash,
+    /// Publish from a temporary `git worktree` checkout of the release tag
+    Worktree,
+}
+
+/// How `rsrepo release` should sign release tags & commits
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SignMethod {
+    /// Sign using the format selected by Git's `gpg.format` config option
+    #[default]
+    Git,
+    /// Sign with a GPG key
+    Gpg,
+    /// Sign with an SSH key
+    Ssh,
+    /// Do not sign
+    None,
+}
+
+impl SignMethod {
+    /// Returns the value for Git's `gpg.format` config option that selects
+    /// this signing method, or `None` if Git's configured format should be
+    /// used or if not signing
+    pub(crate) fn gpg_format(self) -> Option<&'static str> {
+        match self {
+            SignMethod::Gpg => Some("openpgp"),
+            SignMethod::Ssh => Some("ssh"),
+            SignMethod::Git | SignMethod::None => None,
+        }
+    }
+}
+
 impl Config {
     pub(crate) fn load(path: Option<&Path>) -> anyhow::Result<Self> {
         let path: Cow<'_, Path> = match path {
@@ -30,3 +213,47 @@ impl Config {
         Ok(home.join(".config").join("rsrepo.toml"))
     }
 }
+
+impl ProjectConfig {
+    /// Load the settings from the given configuration file or, if `path` is
+    /// `None`, from the default configuration file, in which case the default
+    /// settings are returned if the file does not exist
+    pub(crate) fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let src = match path {
+            Some(p) => read_to_string(p)?,
+            None => match read_to_string(Config::default_path()?) {
+                Ok(src) => src,
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(ProjectConfig::default()),
+                Err(e) => return Err(e.into()),
+            },
+        };
+        toml::from_str::<ProjectConfig>(&src).context("Failed to deserialize config file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks_one_or_many() {
+        let src = concat!(
+            "author = \"John T. Wodder II\"\n",
+            "author-email = \"rsrepo@varonathe.org\"\n",
+            "\n",
+            "[release.hooks]\n",
+            "pre-commit = \"cargo xtask codegen\"\n",
+            "post-release = [\"./notify.sh\", \"echo 'Released!'\"]\n",
+        );
+        let config = toml::from_str::<ProjectConfig>(src).unwrap();
+        assert_eq!(
+            config.release.hooks,
+            Hooks {
+                pre_commit: vec!["cargo xtask codegen".into()],
+                pre_publish: Vec::new(),
+                post_publish: Vec::new(),
+                post_release: vec!["./notify.sh".into(), "echo 'Released!'".into()],
+            }
+        );
+    }
+}
@@ -78,11 +78,19 @@ impl<'a> Git<'a> {
             .context("Error parsing Git commit years")
     }
 
+    /// Returns the most recently-created tag with the given prefix that is
+    /// reachable from `HEAD`, so that tags made on other branches (e.g.,
+    /// maintenance branches) are not considered
     pub(crate) fn latest_tag(
         &self,
         prefix: Option<&str>,
     ) -> Result<Option<String>, CommandOutputError> {
-        let mut args = vec![String::from("-l"), String::from("--sort=-creatordate")];
+        let mut args = vec![
+            String::from("-l"),
+            String::from("--sort=-creatordate"),
+            String::from("--merged"),
+            String::from("HEAD"),
+        ];
         if let Some(pre) = prefix {
             args.push(format!("{pre}*"));
         }
@@ -108,6 +116,31 @@ impl<'a> Git<'a> {
         }
     }
 
+    /// Returns the highest version among the tags with the given prefix,
+    /// ignoring any tags that are not versions
+    pub(crate) fn max_tag_version(
+        &self,
+        prefix: Option<&str>,
+    ) -> Result<Option<Version>, CommandOutputError> {
+        let mut args = vec![String::from("-l")];
+        if let Some(pre) = prefix {
+            args.push(format!("{pre}*"));
+        }
+        Ok(self
+            .readlines("tag", args)?
+            .filter_map(|tag| {
+                let tagv = match prefix {
+                    Some(pre) => tag.strip_prefix(pre)?,
+                    None => &*tag,
+                };
+                tagv.strip_prefix('v')
+                    .unwrap_or(tagv)
+                    .parse::<Version>()
+                    .ok()
+            })
+            .max())
+    }
+
     pub(crate) fn current_branch(&self) -> Result<Option<String>, CommandOutputError> {
         match self.read("symbolic-ref", ["--short", "-q", "HEAD"]) {
             Ok(branch) => Ok(Some(branch)),
@@ -141,13 +174,138 @@ impl<'a> Git<'a> {
         }
     }
 
+    /// Returns the value of the given Git config option, or `None` if it is
+    /// not set
+    pub(crate) fn config_get(&self, key: &str) -> Result<Option<String>, CommandOutputError> {
+        match self.read("config", ["--get", key]) {
+            Ok(s) => Ok(Some(s)),
+            Err(CommandOutputError::Exit { rc, .. }) if rc.code() == Some(1) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns true if there are uncommitted changes to tracked files
+    pub(crate) fn is_dirty(&self) -> Result<bool, CommandOutputError> {
+        self.read("status", ["--porcelain", "--untracked-files=no"])
+            .map(|s| !s.is_empty())
+    }
+
+    /// Returns the number of commits that `HEAD` is ahead of and behind
+    /// `upstream`, respectively
+    pub(crate) fn ahead_behind(&self, upstream: &str) -> anyhow::Result<(usize, usize)> {
+        let s = self.read(
+            "rev-list",
+            ["--left-right", "--count", &format!("HEAD...{upstream}")],
+        )?;
+        let parse = |count: Option<&str>| {
+            count
+                .and_then(|c| c.parse::<usize>().ok())
+                .with_context(|| format!("Invalid `git rev-list --count` output: {s:?}"))
+        };
+        let mut counts = s.split_whitespace();
+        Ok((parse(counts.next())?, parse(counts.next())?))
+    }
+
+    /// Returns true if any files matching the given pathspecs differ between
+    /// `rev` and the working tree
+    pub(crate) fn has_changes_since<I, S>(
+        &self,
+        rev: &str,
+        pathspecs: I,
+    ) -> Result<bool, CommandError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        match self
+            .command()
+            .arg("diff")
+            .arg("--quiet")
+            .arg(rev)
+            .arg("--")
+            .args(pathspecs)
+            .status()
+        {
+            Ok(()) => Ok(false),
+            Err(CommandError::Exit { rc, .. }) if rc.code() == Some(1) => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn rev_parse(&self, rev: &str) -> Result<String, CommandOutputError> {
+        self.read("rev-parse", ["--verify", &format!("{rev}^{{commit}}")])
+    }
+
+    pub(crate) fn has_object(&self, oid: &str) -> Result<bool, CommandError> {
+        match self.run("cat-file", ["-e", oid]) {
+            Ok(()) => Ok(true),
+            Err(CommandError::Exit { rc, .. }) if rc.code() == Some(1) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn is_ancestor(
+        &self,
+        ancestor: &str,
+        descendant: &str,
+    ) -> Result<bool, CommandError> {
+        match self.run("merge-base", ["--is-ancestor", ancestor, descendant]) {
+            Ok(()) => Ok(true),
+            Err(CommandError::Exit { rc, .. }) if rc.code() == Some(1) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Query the given remote for its refs and the object IDs they point to
+    pub(crate) fn remote_refs(&self, remote: &str) -> anyhow::Result<Vec<RemoteRef>> {
+        self.readlines("ls-remote", [remote])?
+            .map(|line| {
+                let (oid, name) = line
+                    .split_once('\t')
+                    .with_context(|| format!("Invalid `git ls-remote` output line: {line:?}"))?;
+                Ok(RemoteRef {
+                    oid: oid.to_owned(),
+                    name: name.to_owned(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns true if `commit` has been pushed to the remote with the given
+    /// refs, i.e., if it is reachable from any of them.  Remote refs pointing
+    /// to objects that do not exist locally cannot contain `commit` and are
+    /// skipped.
+    pub(crate) fn is_pushed(
+        &self,
+        remote_refs: &[RemoteRef],
+        commit: &str,
+    ) -> anyhow::Result<bool> {
+        for rref in remote_refs {
+            if rref.oid == commit {
+                return Ok(true);
+            }
+            if self.has_object(&rref.oid)? && self.is_ancestor(commit, &rref.oid)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     pub(crate) fn toplevel(&self) -> Result<PathBuf, CommandOutputError> {
+        self.read_path("--show-toplevel")
+    }
+
+    pub(crate) fn git_dir(&self) -> Result<PathBuf, CommandOutputError> {
+        self.read_path("--absolute-git-dir")
+    }
+
+    fn read_path(&self, rev_parse_opt: &str) -> Result<PathBuf, CommandOutputError> {
         // Don't use `Git::read()`, as that can strip off too much if the
         // directory name ends in whitespace.
         let mut s = self
             .command()
             .arg("rev-parse")
-            .arg("--show-toplevel")
+            .arg(rev_parse_opt)
             .check_output()?;
         if s.ends_with('\n') {
             s.pop();
@@ -178,9 +336,25 @@ impl<'a> Git<'a> {
     }
 }
 
+/// A ref on a remote repository, as reported by `git ls-remote`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct RemoteRef {
+    pub(crate) oid: String,
+    pub(crate) name: String,
+}
+
+impl RemoteRef {
+    pub(crate) fn is_tag(&self, tag: &str) -> bool {
+        self.name
+            .strip_prefix("refs/tags/")
+            .is_some_and(|t| t.strip_suffix("^{}").unwrap_or(t) == tag)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn toplevel() {
@@ -192,10 +366,23 @@ mod tests {
         assert_eq!(git.toplevel().unwrap(), manifest_dir);
     }
 
+    #[rstest]
+    #[case("refs/tags/v0.1.0", "v0.1.0", true)]
+    #[case("refs/tags/v0.1.0^{}", "v0.1.0", true)]
+    #[case("refs/tags/v0.1.0", "v0.1", false)]
+    #[case("refs/tags/foo/v0.1.0", "v0.1.0", false)]
+    #[case("refs/heads/v0.1.0", "v0.1.0", false)]
+    fn remote_ref_is_tag(#[case] name: &str, #[case] tag: &str, #[case] r: bool) {
+        let rref = RemoteRef {
+            oid: String::from("0123456789abcdef0123456789abcdef01234567"),
+            name: name.to_owned(),
+        };
+        assert_eq!(rref.is_tag(tag), r);
+    }
+
     #[cfg(not(windows))]
     mod not_windows {
         use super::*;
-        use rstest::rstest;
         use tempfile::tempdir;
 
         // These are illegal filenames on Windows.
@@ -75,6 +75,84 @@ impl GitHub {
             .map_err(Into::into)
     }
 
+    /// Find the release for the given tag, including draft releases (which
+    /// the `/releases/tags/{tag}` endpoint does not return)
+    pub(crate) fn find_release_by_tag<R>(
+        &self,
+        repo: &R,
+        tag: &str,
+    ) -> anyhow::Result<Option<Release>>
+    where
+        for<'a> R: RepositoryEndpoint<'a>,
+    {
+        for r in self
+            .0
+            .paginate::<Release>(&format!("{}/releases?per_page=100", repo.api_url()))
+        {
+            let release = r.context("failed to list GitHub releases")?;
+            if release.tag_name == tag {
+                return Ok(Some(release));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn update_release(
+        &self,
+        release: &Release,
+        update: UpdateRelease,
+    ) -> anyhow::Result<Release> {
+        self.0.patch(&release.url, &update).map_err(Into::into)
+    }
+
+    /// Upload `data` as an asset named `name` to the given release
+    pub(crate) fn upload_release_asset(
+        &self,
+        release: &Release,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> anyhow::Result<ReleaseAsset> {
+        let url = release.asset_upload_url(name)?;
+        log::debug!("POST {url}");
+        let mut r = self
+            .0
+            .agent_ref()
+            .post(url.as_str())
+            .header("Content-Type", content_type)
+            .send(data)
+            .with_context(|| format!("failed to upload release asset {name}"))?;
+        if !r.status().is_success() {
+            let body = r.body_mut().read_to_string().unwrap_or_default();
+            anyhow::bail!(
+                "failed to upload release asset {name}: server returned {}: {body}",
+                r.status()
+            );
+        }
+        r.body_mut()
+            .read_json()
+            .with_context(|| format!("failed to parse response to uploading {name}"))
+    }
+
+    /// Fetch the commit statuses and check runs for the commit `sha` and
+    /// summarize them
+    pub(crate) fn ci_status<R>(&self, repo: &R, sha: &str) -> anyhow::Result<CiStatus>
+    where
+        for<'a> R: RepositoryEndpoint<'a>,
+    {
+        let commit_url = format!("{}/commits/{sha}", repo.api_url());
+        let combined = self
+            .0
+            .get::<CombinedStatus>(&format!("{commit_url}/status?per_page=100"))
+            .context("failed to fetch combined commit status")?;
+        let check_runs = self
+            .0
+            .paginate::<CheckRun>(&format!("{commit_url}/check-runs?per_page=100"))
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to fetch check runs")?;
+        Ok(CiStatus::new(&combined.statuses, &check_runs))
+    }
+
     pub(crate) fn get_topics<R>(&self, repo: &R) -> anyhow::Result<Vec<Topic>>
     where
         for<'a> R: RepositoryEndpoint<'a>,
@@ -250,6 +328,10 @@ pub(crate) struct CreateRelease {
     body: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     prerelease: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    make_latest: Option<&'static str>,
 }
 
 impl CreateRelease {
@@ -259,6 +341,8 @@ impl CreateRelease {
             name: None,
             body: None,
             prerelease: None,
+            draft: None,
+            make_latest: None,
         }
     }
 
@@ -276,6 +360,57 @@ impl CreateRelease {
         self.prerelease = Some(prerelease);
         self
     }
+
+    pub(crate) fn draft(mut self, draft: bool) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Set whether the release should be marked as the repository's latest
+    /// release
+    pub(crate) fn make_latest(mut self, make_latest: bool) -> Self {
+        self.make_latest = Some(make_latest_value(make_latest));
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub(crate) struct UpdateRelease {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    make_latest: Option<&'static str>,
+}
+
+impl UpdateRelease {
+    pub(crate) fn new() -> UpdateRelease {
+        UpdateRelease::default()
+    }
+
+    pub(crate) fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub(crate) fn draft(mut self, draft: bool) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Set whether the release should be marked as the repository's latest
+    /// release
+    pub(crate) fn make_latest(mut self, make_latest: bool) -> Self {
+        self.make_latest = Some(make_latest_value(make_latest));
+        self
+    }
+}
+
+/// The API represents `make_latest` as a string in order to accommodate the
+/// additional value "legacy", which we don't use
+fn make_latest_value(make_latest: bool) -> &'static str {
+    if make_latest { "true" } else { "false" }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
@@ -297,7 +432,100 @@ pub(crate) struct Release {
     //pub(crate) created_at: DateTime<FixedOffset>,
     //pub(crate) published_at: DateTime<FixedOffset>,
     //pub(crate) author: SimpleUser,
-    //pub(crate) assets: Vec<ReleaseAsset>,
+    #[serde(default)]
+    pub(crate) assets: Vec<ReleaseAsset>,
+}
+
+impl Release {
+    /// Returns the URL to which to upload an asset named `name`, derived from
+    /// the hypermedia template in `upload_url`
+    pub(crate) fn asset_upload_url(&self, name: &str) -> anyhow::Result<url::Url> {
+        let base = self
+            .upload_url
+            .split_once('{')
+            .map_or(self.upload_url.as_str(), |(pre, _)| pre);
+        let mut url = url::Url::parse(base)
+            .with_context(|| format!("invalid release upload URL: {:?}", self.upload_url))?;
+        url.query_pairs_mut().append_pair("name", name);
+        Ok(url)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub(crate) struct ReleaseAsset {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    pub(crate) browser_download_url: String,
+    pub(crate) size: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct CommitStatus {
+    context: String,
+    /// One of `error`, `failure`, `pending`, or `success`
+    state: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+struct CheckRun {
+    name: String,
+    /// One of `queued`, `in_progress`, `completed`, `waiting`, `requested`,
+    /// or `pending`
+    status: String,
+    /// Only set once the check run is completed
+    conclusion: Option<String>,
+}
+
+/// A summary of the CI results for a commit
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum CiStatus {
+    /// No commit statuses or check runs were reported for the commit
+    Absent,
+    /// All statuses & check runs succeeded (or were skipped)
+    Success,
+    /// Some statuses or check runs have not finished yet; contains their names
+    Pending(Vec<String>),
+    /// Some statuses or check runs failed; contains their names
+    Failure(Vec<String>),
+}
+
+impl CiStatus {
+    fn new(statuses: &[CommitStatus], check_runs: &[CheckRun]) -> CiStatus {
+        if statuses.is_empty() && check_runs.is_empty() {
+            return CiStatus::Absent;
+        }
+        let mut pending = Vec::new();
+        let mut failed = Vec::new();
+        for st in statuses {
+            match st.state.as_str() {
+                "success" => (),
+                "pending" => pending.push(st.context.clone()),
+                _ => failed.push(st.context.clone()),
+            }
+        }
+        for run in check_runs {
+            if run.status != "completed" {
+                pending.push(run.name.clone());
+            } else if !matches!(
+                run.conclusion.as_deref(),
+                Some("success" | "neutral" | "skipped")
+            ) {
+                failed.push(run.name.clone());
+            }
+        }
+        if !failed.is_empty() {
+            CiStatus::Failure(failed)
+        } else if !pending.is_empty() {
+            CiStatus::Pending(pending)
+        } else {
+            CiStatus::Success
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
@@ -341,6 +569,12 @@ fn encrypt_secret(public_key: &str, secret_value: &str) -> anyhow::Result<String
 mod test {
     use super::*;
     use rstest::rstest;
+    use std::path::Path;
+
+    #[derive(Deserialize)]
+    struct CheckRunsPayload {
+        check_runs: Vec<CheckRun>,
+    }
 
     #[rstest]
     #[case("work-in-progress", "work-in-progress")]
@@ -352,4 +586,74 @@ mod test {
             assert_ne!(topic, s);
         }
     }
+
+    #[rstest]
+    #[case("status-none.json", "check-runs-none.json", CiStatus::Absent)]
+    #[case("status-success.json", "check-runs-none.json", CiStatus::Success)]
+    #[case("status-none.json", "check-runs-success.json", CiStatus::Success)]
+    #[case("status-success.json", "check-runs-success.json", CiStatus::Success)]
+    #[case(
+        "status-success.json",
+        "check-runs-pending.json",
+        CiStatus::Pending(vec!["test (ubuntu-latest, stable)".into()])
+    )]
+    #[case(
+        "status-failure.json",
+        "check-runs-pending.json",
+        CiStatus::Failure(vec!["codecov/patch".into()])
+    )]
+    #[case(
+        "status-success.json",
+        "check-runs-failure.json",
+        CiStatus::Failure(vec!["lint".into(), "test (ubuntu-latest, stable)".into()])
+    )]
+    fn ci_status(#[case] status_file: &str, #[case] check_runs_file: &str, #[case] st: CiStatus) {
+        let datadir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("testdata")
+            .join("github");
+        let combined = serde_json::from_str::<CombinedStatus>(
+            &fs_err::read_to_string(datadir.join(status_file)).unwrap(),
+        )
+        .unwrap();
+        let check_runs = serde_json::from_str::<CheckRunsPayload>(
+            &fs_err::read_to_string(datadir.join(check_runs_file)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            CiStatus::new(&combined.statuses, &check_runs.check_runs),
+            st
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "https://uploads.github.com/repos/octocat/Hello-World/releases/1/assets{?name,label}",
+        "foo-1.0.0-x86_64-unknown-linux-gnu.tar.gz",
+        "https://uploads.github.com/repos/octocat/Hello-World/releases/1/assets?name=foo-1.0.0-x86_64-unknown-linux-gnu.tar.gz"
+    )]
+    #[case(
+        "https://uploads.github.com/repos/octocat/Hello-World/releases/1/assets",
+        "foo bar.tar.gz",
+        "https://uploads.github.com/repos/octocat/Hello-World/releases/1/assets?name=foo+bar.tar.gz"
+    )]
+    fn asset_upload_url(#[case] upload_url: &str, #[case] name: &str, #[case] url: &str) {
+        let release = Release {
+            url: "https://api.github.com/repos/octocat/Hello-World/releases/1".into(),
+            html_url: "https://github.com/octocat/Hello-World/releases/v1.0.0".into(),
+            assets_url: "https://api.github.com/repos/octocat/Hello-World/releases/1/assets".into(),
+            upload_url: upload_url.into(),
+            tarball_url: "https://api.github.com/repos/octocat/Hello-World/tarball/v1.0.0".into(),
+            zipball_url: "https://api.github.com/repos/octocat/Hello-World/zipball/v1.0.0".into(),
+            id: 1,
+            tag_name: "v1.0.0".into(),
+            target_commitish: "master".into(),
+            name: "v1.0.0".into(),
+            body: None,
+            draft: false,
+            prerelease: false,
+            assets: Vec::new(),
+        };
+        assert_eq!(release.asset_upload_url(name).unwrap().as_str(), url);
+    }
 }
@@ -33,6 +33,12 @@ struct Arguments {
     #[arg(short = 'c', long, value_name = "FILE")]
     config: Option<PathBuf>,
 
+    /// Use the given Git remote for the GitHub repository [default: the
+    /// `remote` setting in `[package.metadata.rsrepo]` or the configuration
+    /// file, or else "origin"]
+    #[arg(long, value_name = "NAME", global = true)]
+    remote: Option<String>,
+
     /// Set logging level
     #[arg(
         short,
@@ -52,7 +58,7 @@ impl Arguments {
         if let Some(dir) = self.chdir {
             set_current_dir(dir).context("Failed to change directory")?;
         }
-        self.command.run(Provider::new(self.config))
+        self.command.run(Provider::new(self.config, self.remote))
     }
 }
 
@@ -164,6 +170,23 @@ mod tests {
         assert_eq!(args.unwrap_err().kind(), ErrorKind::ArgumentConflict);
     }
 
+    #[test]
+    fn release_major_pre() {
+        let args =
+            Arguments::try_parse_from(["arg0", "release", "--major", "--pre", "alpha"]).unwrap();
+        let Command::Release(rel) = args.command else {
+            panic!("`release` subcommand did not yield `Release` variant");
+        };
+        assert_eq!(rel.bumping.level(), Some(Bump::Major));
+    }
+
+    #[test]
+    fn release_finalize_pre() {
+        let args = Arguments::try_parse_from(["arg0", "release", "--finalize", "--pre", "rc"]);
+        assert!(args.is_err());
+        assert_eq!(args.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
     #[test]
     fn release_major() {
         let args = Arguments::try_parse_from(["arg0", "release", "--major"]).unwrap();
@@ -0,0 +1,170 @@
+use super::Package;
+
+/// The slugs of the categories recognized by crates.io, one per line
+static CATEGORIES: &str = include_str!("data/categories.txt");
+
+/// The maximum number of keywords or categories that crates.io accepts
+const MAX_KEYWORDS: usize = 5;
+
+/// The maximum length of a keyword accepted by crates.io
+const MAX_KEYWORD_LEN: usize = 20;
+
+impl Package {
+    /// Check the package's metadata for problems that would cause `cargo
+    /// publish` to fail or that would make for a poor crates.io listing.
+    /// Returns a description of each problem found.
+    pub(crate) fn lint_manifest(&self) -> Vec<String> {
+        let metadata = self.metadata();
+        let mut problems = Vec::new();
+        if metadata
+            .description
+            .as_deref()
+            .is_none_or(|s| s.trim().is_empty())
+        {
+            problems.push(String::from("`description` is not set"));
+        }
+        if metadata.license.is_none() && metadata.license_file.is_none() {
+            problems.push(String::from("Neither `license` nor `license-file` is set"));
+        }
+        if metadata.repository.is_none() {
+            problems.push(String::from("`repository` is not set"));
+        }
+        if metadata.keywords.len() > MAX_KEYWORDS {
+            problems.push(format!(
+                "More than {MAX_KEYWORDS} keywords given ({})",
+                metadata.keywords.len()
+            ));
+        }
+        for kw in &metadata.keywords {
+            if !is_valid_keyword(kw) {
+                problems.push(format!(
+                    "Invalid keyword {kw:?}: keywords must be at most {MAX_KEYWORD_LEN} characters, start with an ASCII letter or digit, and contain only ASCII letters, digits, '_', '-', and '+'"
+                ));
+            }
+        }
+        if metadata.categories.len() > MAX_KEYWORDS {
+            problems.push(format!(
+                "More than {MAX_KEYWORDS} categories given ({})",
+                metadata.categories.len()
+            ));
+        }
+        for cat in &metadata.categories {
+            if !is_valid_category(cat) {
+                problems.push(format!(
+                    "Unknown category {cat:?}; see <https://crates.io/category_slugs>"
+                ));
+            }
+        }
+        if self.is_lib()
+            && let Some(ref docs) = metadata.documentation
+            && docs.starts_with("https://github.com/")
+        {
+            problems.push(format!(
+                "`documentation` points to GitHub ({docs}) even though the package has a library crate; remove it to use docs.rs"
+            ));
+        }
+        problems
+    }
+}
+
+fn is_valid_keyword(kw: &str) -> bool {
+    kw.len() <= MAX_KEYWORD_LEN
+        && kw.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && kw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+}
+
+fn is_valid_category(cat: &str) -> bool {
+    CATEGORIES.lines().any(|ln| ln == cat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Project;
+    use assert_fs::{TempDir, prelude::*};
+    use indoc::indoc;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("cli", true)]
+    #[case("no-std", true)]
+    #[case("c++", true)]
+    #[case("2d", true)]
+    #[case("a_b", true)]
+    #[case("", false)]
+    #[case("-cli", false)]
+    #[case("command line", false)]
+    #[case("configuration-files-x", false)]
+    #[case("café", false)]
+    fn test_is_valid_keyword(#[case] kw: &str, #[case] ok: bool) {
+        assert_eq!(is_valid_keyword(kw), ok);
+    }
+
+    #[rstest]
+    #[case("command-line-utilities", true)]
+    #[case("development-tools::cargo-plugins", true)]
+    #[case("web-programming::http-client", true)]
+    #[case("command-line-tools", false)]
+    #[case("cargo-plugins", false)]
+    #[case("", false)]
+    fn test_is_valid_category(#[case] cat: &str, #[case] ok: bool) {
+        assert_eq!(is_valid_category(cat), ok);
+    }
+
+    fn lint(manifest_src: &str) -> Vec<String> {
+        let tmpdir = TempDir::new().unwrap();
+        let manifest = tmpdir.child("Cargo.toml");
+        manifest.write_str(manifest_src).unwrap();
+        tmpdir.child("src").create_dir_all().unwrap();
+        tmpdir.child("src").child("lib.rs").touch().unwrap();
+        Project::for_manifest_path(manifest.path())
+            .unwrap()
+            .package_set()
+            .unwrap()
+            .into_root_package()
+            .unwrap()
+            .lint_manifest()
+    }
+
+    #[test]
+    fn lint_good() {
+        let problems = lint(indoc! {r#"
+            [package]
+            name = "foobar"
+            version = "0.1.0"
+            edition = "2021"
+            description = "Foo all the bars"
+            repository = "https://github.com/jwodder/foobar"
+            license = "MIT"
+            keywords = ["foo", "bar"]
+            categories = ["rust-patterns"]
+        "#});
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn lint_bad() {
+        let problems = lint(indoc! {r#"
+            [package]
+            name = "foobar"
+            version = "0.1.0"
+            edition = "2021"
+            documentation = "https://github.com/jwodder/foobar"
+            keywords = ["foo", "bar", "baz", "quux", "glarch", "cleesh"]
+            categories = ["rust-pattern"]
+        "#});
+        assert_eq!(
+            problems,
+            [
+                "`description` is not set",
+                "Neither `license` nor `license-file` is set",
+                "`repository` is not set",
+                "More than 5 keywords given (6)",
+                "Unknown category \"rust-pattern\"; see <https://crates.io/category_slugs>",
+                "`documentation` points to GitHub (https://github.com/jwodder/foobar) even though the package has a library crate; remove it to use docs.rs",
+            ]
+        );
+    }
+}
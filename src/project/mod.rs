@@ -1,3 +1,4 @@
+mod lint;
 mod package;
 mod pkgset;
 mod textfile;
@@ -10,10 +11,11 @@ pub(crate) use self::traits::HasReadme;
 use self::util::locate_project;
 use crate::git::Git;
 use crate::readme::Readme;
+use crate::util::workspace_tag_prefix;
 use anyhow::{Context, bail};
-use cargo_metadata::{MetadataCommand, semver::VersionReq};
+use cargo_metadata::{DependencyKind, MetadataCommand, semver::VersionReq};
 use serde::Deserialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 use toml_edit::DocumentMut;
@@ -92,6 +94,9 @@ impl Project {
         // Mapping from package names to the names of the packages that depend
         // on them and their version reqs
         let mut rdeps: BTreeMap<String, BTreeMap<String, VersionReq>> = BTreeMap::new();
+        // Mapping from package names to the names of the packages that depend
+        // on them other than as a dev-dependency
+        let mut non_dev_rdeps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
         for md in package_metadata {
             if packages.contains_key(md.name.as_ref()) {
                 anyhow::bail!(
@@ -110,6 +115,12 @@ impl Project {
                         .entry(dep.name.clone())
                         .or_default()
                         .insert(md.name.to_string(), dep.req.clone());
+                    if dep.kind != DependencyKind::Development {
+                        non_dev_rdeps
+                            .entry(dep.name.clone())
+                            .or_default()
+                            .insert(md.name.to_string());
+                    }
                 }
             }
             let name = md.name.to_string();
@@ -118,12 +129,36 @@ impl Project {
         let mut package_vec = Vec::with_capacity(packages.len());
         for (pkgname, (md, root)) in packages {
             let dependents = rdeps.remove(&pkgname).unwrap_or_default();
-            package_vec.push(Package::new(md, root, dependents));
+            let non_dev_dependents = non_dev_rdeps.remove(&pkgname).unwrap_or_default();
+            package_vec.push(Package::new(md, root, dependents, non_dev_dependents));
         }
         // TODO: Warn if `rdeps` is non-empty?
         Ok(PackageSet::new(package_vec))
     }
 
+    /// Returns the directory in which Cargo places build artifacts for the
+    /// project
+    pub(crate) fn target_directory(&self) -> anyhow::Result<PathBuf> {
+        log::debug!("Running `cargo metadata`");
+        let metadata = MetadataCommand::new()
+            .manifest_path(&self.manifest_path)
+            .no_deps()
+            .exec()
+            .context("Failed to get project metadata")?;
+        Ok(metadata.target_directory.into_std_path_buf())
+    }
+
+    /// Returns the prefix (if any) to use for the names of the release tags
+    /// of `package`, a member of `pkgset`
+    pub(crate) fn tag_prefix(&self, pkgset: &PackageSet, package: &Package) -> Option<String> {
+        let needs_prefix = if package.is_public() {
+            pkgset.iter().filter(|p| p.is_public()).count() != 1
+        } else {
+            self.project_type().is_workspace()
+        };
+        needs_prefix.then(|| workspace_tag_prefix(package.name()))
+    }
+
     pub(crate) fn manifest(&self) -> TextFile<'_, DocumentMut> {
         TextFile::new(self.path(), "Cargo.toml")
     }
@@ -1,8 +1,9 @@
 use super::textfile::TextFile;
 use super::traits::HasReadme;
 use super::{Flavor, PackageSet, Project};
-use crate::changelog::{Changelog, ChangelogHeader, ChangelogSection};
+use crate::changelog::{Changelog, ChangelogCategory, ChangelogHeader, ChangelogSection};
 use crate::cmd::LoggedCommand;
+use crate::config::{PackageConfig, VersionFile};
 use crate::readme::Readme;
 use crate::util::{Bump, CopyrightLine, bump_version};
 use anyhow::{Context, bail};
@@ -11,10 +12,11 @@ use cargo_metadata::{
     semver::{Op, Prerelease, Version, VersionReq},
 };
 use in_place::InPlace;
-use std::collections::BTreeMap;
-use std::fmt::Write as _;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use toml_edit::DocumentMut;
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -22,6 +24,7 @@ pub(crate) struct Package {
     metadata: CargoPackage,
     is_root: bool,
     dependents: BTreeMap<String, VersionReq>,
+    non_dev_dependents: BTreeSet<String>,
 }
 
 impl Package {
@@ -29,11 +32,13 @@ impl Package {
         metadata: CargoPackage,
         is_root: bool,
         dependents: BTreeMap<String, VersionReq>,
+        non_dev_dependents: BTreeSet<String>,
     ) -> Package {
         Package {
             metadata,
             is_root,
             dependents,
+            non_dev_dependents,
         }
     }
 
@@ -76,6 +81,20 @@ impl Package {
         &self.metadata
     }
 
+    /// Returns the settings in the package's `[package.metadata.rsrepo]`
+    /// table
+    pub(crate) fn config(&self) -> anyhow::Result<PackageConfig> {
+        match self.metadata.metadata.get("rsrepo") {
+            Some(value) => PackageConfig::deserialize(value).with_context(|| {
+                format!(
+                    "Failed to deserialize [package.metadata.rsrepo] for {}",
+                    self.name()
+                )
+            }),
+            None => Ok(PackageConfig::default()),
+        }
+    }
+
     pub(crate) fn name(&self) -> &str {
         self.metadata.name.as_ref()
     }
@@ -88,10 +107,48 @@ impl Package {
         &self.dependents
     }
 
+    /// Returns the names of the packages that depend on this package other
+    /// than solely as a dev-dependency
+    pub(crate) fn non_dev_dependents(&self) -> &BTreeSet<String> {
+        &self.non_dev_dependents
+    }
+
+    /// Returns the packages that depend on this package whose version
+    /// requirements need to be updated if this package is set to `version`
+    pub(crate) fn outdated_dependents(&self, version: &Version) -> Vec<(&str, &VersionReq)> {
+        self.dependents
+            .iter()
+            // When a package `foo`'s version is bumped from `0.3.0-dev` to
+            // `0.3.0`, any package `bar` that depends on `foo 0.3.0-dev`
+            // should have its version requirement bumped to `0.3.0`, but
+            // Cargo's semver rules mean that `^0.3.0-dev` accepts `0.3.0`.
+            // Thus, if `req` using a prelease does not equal `version` being
+            // a prerelease, bump.
+            .filter(|(_, req)| {
+                !req.matches(version) || uses_prerelease(req) == version.pre.is_empty()
+            })
+            .map(|(rname, req)| (rname.as_str(), req))
+            .collect()
+    }
+
     pub(crate) fn is_public(&self) -> bool {
         self.metadata.publish.as_deref() != Some(&[])
     }
 
+    /// Returns the name of the registry that the package should be published
+    /// to, or `None` for crates.io.  If `publish` in `Cargo.toml` lists
+    /// multiple registries, crates.io is preferred if it's among them;
+    /// otherwise, the first one is used.  The return value is meaningless if
+    /// the package is not public.
+    pub(crate) fn publish_registry(&self) -> Option<&str> {
+        match self.metadata.publish.as_deref() {
+            Some(registries) if !registries.iter().any(|r| r == CRATES_IO_REGISTRY) => {
+                registries.first().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn changelog(&self) -> TextFile<'_, Changelog> {
         TextFile::new(self.path(), "CHANGELOG.md")
     }
@@ -136,6 +193,54 @@ impl Package {
         Ok(())
     }
 
+    /// Apply the search & replace operations configured in
+    /// `[[package.metadata.rsrepo.version-files]]` for the given version.  If
+    /// `dev` is true, only operations for which `begin-dev` is enabled are
+    /// applied.  It is an error for an operation's pattern to not match.
+    pub(crate) fn update_version_files(&self, version: &Version, dev: bool) -> anyhow::Result<()> {
+        for vf in self.config()?.version_files {
+            if dev && !vf.begin_dev {
+                continue;
+            }
+            log::info!("Updating version in {} ...", vf.path.display());
+            let (path, content) = self.replace_version(&vf, version)?;
+            fs_err::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Check that the pattern of every operation in
+    /// `[[package.metadata.rsrepo.version-files]]` matches its file
+    pub(crate) fn check_version_files(&self) -> anyhow::Result<()> {
+        for vf in self.config()?.version_files {
+            self.replace_version(&vf, &self.metadata.version)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the path to the file for `vf` along with the file's new
+    /// content after applying the replacement for `version`
+    fn replace_version(
+        &self,
+        vf: &VersionFile,
+        version: &Version,
+    ) -> anyhow::Result<(PathBuf, String)> {
+        let rgx = Regex::new(&vf.search)
+            .with_context(|| format!("Invalid version-files search pattern {:?}", vf.search))?;
+        let path = self.path().join(&vf.path);
+        let content = fs_err::read_to_string(&path)?;
+        if !rgx.is_match(&content) {
+            bail!(
+                "version-files search pattern {:?} did not match anything in {}",
+                vf.search,
+                vf.path.display()
+            );
+        }
+        let replacement = expand_version_placeholders(&vf.replace, version);
+        let content = rgx.replace_all(&content, replacement.as_str()).into_owned();
+        Ok((path, content))
+    }
+
     pub(crate) fn update_lockfile(&self, v: &Version) -> anyhow::Result<()> {
         LoggedCommand::new("cargo")
             .arg("update")
@@ -235,6 +340,33 @@ impl Package {
         Ok(())
     }
 
+    /// If the package has a `CHANGELOG.md`, begin development on the next
+    /// version (if not already begun) and add an entry to the topmost section
+    /// of the changelog as described in [`Changelog::add_entry()`].  Returns
+    /// `false` if the package does not have a `CHANGELOG.md`.
+    pub(crate) fn add_changelog_entry(
+        &self,
+        package_set: &PackageSet,
+        text: &str,
+        category: Option<ChangelogCategory>,
+        replacing: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        let chlog_file = self.changelog();
+        if !chlog_file.exists() {
+            return Ok(false);
+        }
+        self.begin_dev(package_set).quiet(true).run()?;
+        let Some(mut chlog) = chlog_file.get()? else {
+            return Ok(false);
+        };
+        log::info!("Updating CHANGELOG.md for {} ...", self.name());
+        if !chlog.add_entry(text, category, replacing) {
+            bail!("CHANGELOG.md for {} has no sections", self.name());
+        }
+        chlog_file.set(chlog)?;
+        Ok(true)
+    }
+
     pub(crate) fn begin_dev<'a>(&'a self, package_set: &'a PackageSet) -> BeginDev<'a> {
         BeginDev::new(self, package_set)
     }
@@ -260,6 +392,8 @@ pub(crate) struct BeginDev<'a> {
     package: &'a Package,
     pkgset: &'a PackageSet,
     latest_release: Option<(Version, chrono::NaiveDate)>,
+    level: Bump,
+    version: Option<Version>,
     quiet: bool,
     force: bool,
 }
@@ -270,6 +404,8 @@ impl<'a> BeginDev<'a> {
             package,
             pkgset: package_set,
             latest_release: None,
+            level: Bump::Minor,
+            version: None,
             quiet: false,
             force: false,
         }
@@ -280,6 +416,20 @@ impl<'a> BeginDev<'a> {
         self
     }
 
+    /// Set the level at which to bump the latest release version to get the
+    /// next version.  The default is [`Bump::Minor`].
+    pub(crate) fn bump(mut self, level: Bump) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Begin development on the given version (sans "-dev" suffix) instead of
+    /// one calculated from the latest release
+    pub(crate) fn version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
     pub(crate) fn quiet(mut self, yes: bool) -> Self {
         self.quiet = yes;
         self
@@ -290,6 +440,28 @@ impl<'a> BeginDev<'a> {
         self
     }
 
+    /// Returns the version (sans "-dev" suffix) that development will begin
+    /// on.  After a prerelease, development continues on the corresponding
+    /// final release.
+    pub(crate) fn next_version(&self) -> Version {
+        if let Some(ref v) = self.version {
+            return v.clone();
+        }
+        let latest_version = match self.latest_release {
+            Some((ref version, _)) => version.clone(),
+            None => self.package.metadata().version.clone(),
+        };
+        if latest_version.pre.is_empty() {
+            bump_version(latest_version, self.level)
+        } else {
+            Version::new(
+                latest_version.major,
+                latest_version.minor,
+                latest_version.patch,
+            )
+        }
+    }
+
     pub(crate) fn run(self) -> anyhow::Result<()> {
         let current_version = &self.package.metadata().version;
         if !self.force && !current_version.pre.is_empty() {
@@ -300,11 +472,7 @@ impl<'a> BeginDev<'a> {
         }
 
         log::info!("Preparing for work on next version ...");
-        let latest_version = match self.latest_release {
-            Some((ref version, _)) => version.clone(),
-            None => current_version.clone(),
-        };
-        let next_version = bump_version(latest_version, Bump::Minor);
+        let next_version = self.next_version();
         let mut dev_next = next_version.clone();
         dev_next.pre =
             Prerelease::new("dev").expect("'dev' should be a valid prerelease identifier");
@@ -313,6 +481,7 @@ impl<'a> BeginDev<'a> {
         log::info!("Setting next version in Cargo.toml ...");
         self.package
             .set_version_and_bump_dependents(&dev_next, self.pkgset)?;
+        self.package.update_version_files(&dev_next, true)?;
 
         // If `self.latest_release` is set, ensure CHANGELOG exists
         let chlog_file = self.package.changelog();
@@ -322,29 +491,38 @@ impl<'a> BeginDev<'a> {
         {
             chlog = Some(Changelog {
                 sections: vec![ChangelogSection {
-                    header: ChangelogHeader::Released { version, date },
+                    header: ChangelogHeader::Released {
+                        version,
+                        date,
+                        yanked: false,
+                    },
                     content: "Initial release\n".into(),
                 }],
+                ..Changelog::default()
             });
         }
         // If CHANGELOG exists, ensure it contains section for upcoming version
         if let Some(mut chlog) = chlog {
-            if chlog
-                .sections
-                .first()
-                .is_none_or(|sect| matches!(sect.header, ChangelogHeader::Released { .. }))
-            {
-                log::info!("Adding next section to CHANGELOG.md ...");
-                chlog.sections.insert(
-                    0,
-                    ChangelogSection {
-                        header: ChangelogHeader::InProgress {
-                            version: next_version,
+            match chlog.sections.first_mut().map(|sect| &mut sect.header) {
+                Some(ChangelogHeader::InProgress { version }) => {
+                    if *version != next_version {
+                        log::info!("Updating version in CHANGELOG.md section header ...");
+                        *version = next_version;
+                        chlog_file.set(chlog)?;
+                    }
+                }
+                Some(ChangelogHeader::InDevelopment) => (),
+                Some(ChangelogHeader::Released { .. }) | None => {
+                    log::info!("Adding next section to CHANGELOG.md ...");
+                    chlog.sections.insert(
+                        0,
+                        ChangelogSection {
+                            header: chlog.format.dev_header(next_version),
+                            content: String::new(),
                         },
-                        content: String::new(),
-                    },
-                );
-                chlog_file.set(chlog)?;
+                    );
+                    chlog_file.set(chlog)?;
+                }
             }
         } else {
             log::info!("No CHANGELOG.md file to add next section to");
@@ -353,53 +531,36 @@ impl<'a> BeginDev<'a> {
     }
 }
 
+/// Replace the placeholders `{version}`, `{major}`, `{minor}`, and `{patch}`
+/// in `template` with the corresponding values for `version`
+fn expand_version_placeholders(template: &str, version: &Version) -> String {
+    template
+        .replace("{version}", &version.to_string())
+        .replace("{major}", &version.major.to_string())
+        .replace("{minor}", &version.minor.to_string())
+        .replace("{patch}", &version.patch.to_string())
+}
+
+/// The name by which crates.io is referred to in `publish` lists
+static CRATES_IO_REGISTRY: &str = "crates-io";
+
 fn bump_dependents(
     pkgset: &PackageSet,
     package: &Package,
     version: &Version,
 ) -> anyhow::Result<()> {
     let name = package.name();
-    for (rname, req) in package.dependents() {
-        // When a package `foo`'s version is bumped from `0.3.0-dev` to
-        // `0.3.0`, any package `bar` that depends on `foo 0.3.0-dev` should
-        // have its version requirement bumped to `0.3.0`, but Cargo's semver
-        // rules mean that `^0.3.0-dev` accepts `0.3.0`.  Thus, if `req` using
-        // a prelease does not equal `version` being a prerelease, bump.
-        if !req.matches(version) || uses_prerelease(req) == version.pre.is_empty() {
-            let Some(rpkg) = pkgset.package_by_name(rname) else {
-                bail!(
-                    "Inconsistent project metadata: {name} is depended on by {rname}, but the latter was not found"
-                );
-            };
-            log::info!("Updating {rname}'s dependency on {name} ...");
-            let changed = rpkg.set_dependency_version(name, version.to_string(), false)?;
-            if version.pre.is_empty() && changed.contains(&"dependencies") {
-                let chlog_file = rpkg.changelog();
-                if chlog_file.exists() {
-                    rpkg.begin_dev(pkgset).quiet(true).run()?;
-                    if let Some(mut chlog) = chlog_file.get()?
-                        && let Some(most_recent) = chlog.sections.first_mut()
-                    {
-                        log::info!("Updating CHANGELOG.md for {rname} ...");
-                        let prefix = format!("- Increase `{name}` dependency to ");
-                        let mut new_content = String::with_capacity(most_recent.content.len());
-                        let mut changed = false;
-                        for ln in most_recent.content.lines() {
-                            if !changed && ln.starts_with(&prefix) {
-                                let _ = writeln!(&mut new_content, "{prefix}`{version}`");
-                                changed = true;
-                            } else {
-                                let _ = writeln!(&mut new_content, "{ln}");
-                            }
-                        }
-                        if !changed {
-                            let _ = writeln!(&mut new_content, "{prefix}`{version}`");
-                        }
-                        most_recent.content = new_content;
-                        chlog_file.set(chlog)?;
-                    }
-                }
-            }
+    for (rname, _) in package.outdated_dependents(version) {
+        let Some(rpkg) = pkgset.package_by_name(rname) else {
+            bail!(
+                "Inconsistent project metadata: {name} is depended on by {rname}, but the latter was not found"
+            );
+        };
+        log::info!("Updating {rname}'s dependency on {name} ...");
+        let changed = rpkg.set_dependency_version(name, version.to_string(), false)?;
+        if version.pre.is_empty() && changed.contains(&"dependencies") {
+            let prefix = format!("Increase `{name}` dependency to ");
+            rpkg.add_changelog_entry(pkgset, &format!("{prefix}`{version}`"), None, Some(&prefix))?;
         }
     }
     Ok(())
@@ -417,6 +578,7 @@ mod tests {
     use crate::project::Project;
     use assert_fs::{TempDir, fixture::ChildPath, prelude::*};
     use indoc::indoc;
+    use rstest::rstest;
 
     struct TestPackage {
         package: Package,
@@ -505,6 +667,106 @@ mod tests {
         }
     }
 
+    mod update_version_files {
+        use super::*;
+
+        fn version_files_package() -> TestPackage {
+            let tpkg = TestPackage::new(indoc! {r#"
+                [package]
+                name = "foobar"
+                version = "1.2.3"
+                edition = "2021"
+
+                [[package.metadata.rsrepo.version-files]]
+                path = "README.md"
+                search = 'foobar = "\d+\.\d+"'
+                replace = 'foobar = "{major}.{minor}"'
+                begin-dev = false
+
+                [[package.metadata.rsrepo.version-files]]
+                path = "src/main.rs"
+                search = '(html_root_url = "https://docs.rs/foobar/)[^"]+"'
+                replace = '${1}{version}"'
+            "#});
+            tpkg.tmpdir
+                .child("README.md")
+                .write_str("```toml\n[dependencies]\nfoobar = \"1.2\"\n```\n")
+                .unwrap();
+            tpkg.tmpdir
+                .child("src")
+                .child("main.rs")
+                .write_str(
+                    "#![doc(html_root_url = \"https://docs.rs/foobar/1.2.3\")]\nfn main() {}\n",
+                )
+                .unwrap();
+            tpkg
+        }
+
+        #[test]
+        fn release() {
+            let tpkg = version_files_package();
+            tpkg.package.check_version_files().unwrap();
+            tpkg.package
+                .update_version_files(&Version::new(1, 3, 0), false)
+                .unwrap();
+            tpkg.tmpdir
+                .child("README.md")
+                .assert("```toml\n[dependencies]\nfoobar = \"1.3\"\n```\n");
+            tpkg.tmpdir.child("src").child("main.rs").assert(
+                "#![doc(html_root_url = \"https://docs.rs/foobar/1.3.0\")]\nfn main() {}\n",
+            );
+        }
+
+        #[test]
+        fn dev() {
+            let tpkg = version_files_package();
+            tpkg.package
+                .update_version_files(&Version::parse("1.3.0-dev").unwrap(), true)
+                .unwrap();
+            tpkg.tmpdir
+                .child("README.md")
+                .assert("```toml\n[dependencies]\nfoobar = \"1.2\"\n```\n");
+            tpkg.tmpdir.child("src").child("main.rs").assert(
+                "#![doc(html_root_url = \"https://docs.rs/foobar/1.3.0-dev\")]\nfn main() {}\n",
+            );
+        }
+
+        #[test]
+        fn no_match() {
+            let tpkg = version_files_package();
+            tpkg.tmpdir
+                .child("README.md")
+                .write_str("No install instructions here\n")
+                .unwrap();
+            assert!(tpkg.package.check_version_files().is_err());
+            assert!(
+                tpkg.package
+                    .update_version_files(&Version::new(1, 3, 0), false)
+                    .is_err()
+            );
+        }
+    }
+
+    #[rstest]
+    #[case("", true, None)]
+    #[case("publish = false", false, None)]
+    #[case("publish = []", false, None)]
+    #[case(r#"publish = ["crates-io"]"#, true, None)]
+    #[case(r#"publish = ["internal"]"#, true, Some("internal"))]
+    #[case(r#"publish = ["internal", "other"]"#, true, Some("internal"))]
+    #[case(r#"publish = ["internal", "crates-io"]"#, true, None)]
+    fn publish_registry(
+        #[case] publish: &str,
+        #[case] public: bool,
+        #[case] registry: Option<&str>,
+    ) {
+        let tpkg = TestPackage::new(&format!(
+            "[package]\nname = \"foobar\"\nversion = \"0.1.0\"\nedition = \"2021\"\n{publish}\n"
+        ));
+        assert_eq!(tpkg.package.is_public(), public);
+        assert_eq!(tpkg.package.publish_registry(), registry);
+    }
+
     #[test]
     fn update_license_years() {
         let tpkg = TestPackage::new(indoc! {r#"
@@ -1,5 +1,7 @@
 use super::package::Package;
 use super::util::{LocateError, locate_project};
+use anyhow::bail;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -57,6 +59,51 @@ impl PackageSet {
             .find(|p| p.manifest_path() == manifest_path)
     }
 
+    /// Returns all packages in the set, sorted so that each package comes
+    /// after all of the packages in the set that it depends on.
+    /// Dev-dependencies are ignored, as Cargo allows them to form cycles.
+    /// Packages that are not ordered relative to each other are sorted by
+    /// name.
+    pub(crate) fn dependency_order(&self) -> anyhow::Result<Vec<&Package>> {
+        // Mapping from package names to the number of their dependencies in
+        // the set that have not yet been ordered
+        let mut pending = self
+            .packages
+            .iter()
+            .map(|p| (p.name(), 0usize))
+            .collect::<BTreeMap<_, _>>();
+        for p in &self.packages {
+            for rname in p.non_dev_dependents() {
+                if let Some(n) = pending.get_mut(rname.as_str()) {
+                    *n += 1;
+                }
+            }
+        }
+        let mut ordered = Vec::with_capacity(self.packages.len());
+        while let Some(name) = pending
+            .iter()
+            .find_map(|(&name, &n)| (n == 0).then_some(name))
+        {
+            pending.remove(name);
+            let package = self
+                .package_by_name(name)
+                .expect("package names in `pending` should be in set");
+            for rname in package.non_dev_dependents() {
+                if let Some(n) = pending.get_mut(rname.as_str()) {
+                    *n -= 1;
+                }
+            }
+            ordered.push(package);
+        }
+        if !pending.is_empty() {
+            bail!(
+                "Dependency cycle detected among packages: {}",
+                pending.into_keys().collect::<Vec<_>>().join(", ")
+            );
+        }
+        Ok(ordered)
+    }
+
     pub(crate) fn current_package(&self) -> Result<Option<&Package>, LocateError> {
         locate_project(false).map(|path| self.package_by_manifest_path(&path))
     }
@@ -1,25 +1,30 @@
-use crate::config::Config;
+use crate::config::{Config, ProjectConfig};
 use crate::github::GitHub;
+use crate::project::Package;
 use once_cell::unsync::OnceCell;
 use std::path::PathBuf;
 
 #[derive(Clone, Debug)]
 pub(crate) struct Provider {
     config_path: Option<PathBuf>,
+    remote: Option<String>,
     // We need to use OnceCell instead of plain Options so that multiple
     // methods can be called on a Provider without causing a compilation error
     // due to multiple mutable borrows.  In addition, we need to use the
     // OnceCell from once_cell because the std OnceCell's get_or_try_init() is
     // still unstable.
     config: OnceCell<Config>,
+    project_config: OnceCell<ProjectConfig>,
     github: OnceCell<GitHub>,
 }
 
 impl Provider {
-    pub(crate) fn new(config_path: Option<PathBuf>) -> Provider {
+    pub(crate) fn new(config_path: Option<PathBuf>, remote: Option<String>) -> Provider {
         Provider {
             config_path,
+            remote,
             config: OnceCell::new(),
+            project_config: OnceCell::new(),
             github: OnceCell::new(),
         }
     }
@@ -29,6 +34,34 @@ impl Provider {
             .get_or_try_init(|| Config::load(self.config_path.as_deref()))
     }
 
+    pub(crate) fn project_config(&self) -> anyhow::Result<&ProjectConfig> {
+        self.project_config
+            .get_or_try_init(|| ProjectConfig::load(self.config_path.as_deref()))
+    }
+
+    /// Returns the name of the Git remote to push to and to determine the
+    /// GitHub repository from: the `--remote` option if given, else the
+    /// `remote` setting in the package's `[package.metadata.rsrepo]` (if a
+    /// package is given), else the `remote` setting in the configuration file,
+    /// else "origin"
+    pub(crate) fn remote(&self, package: Option<&Package>) -> anyhow::Result<String> {
+        if let Some(ref remote) = self.remote {
+            return Ok(remote.clone());
+        }
+        if let Some(remote) = package
+            .map(Package::config)
+            .transpose()?
+            .and_then(|c| c.remote)
+        {
+            return Ok(remote);
+        }
+        Ok(self
+            .project_config()?
+            .remote
+            .clone()
+            .unwrap_or_else(|| String::from("origin")))
+    }
+
     pub(crate) fn github(&self) -> anyhow::Result<&GitHub> {
         self.github.get_or_try_init(GitHub::authed)
     }
@@ -1,5 +1,6 @@
-use cargo_metadata::semver::Version;
-use chrono::Datelike;
+use anyhow::{Context, bail};
+use cargo_metadata::semver::{Prerelease, Version};
+use chrono::{Datelike, NaiveDate};
 use fs_err::{create_dir_all, read_dir, remove_dir};
 use rangemap::RangeInclusiveSet;
 use renamore::rename_exclusive;
@@ -27,6 +28,27 @@ pub(crate) fn this_year() -> i32 {
     chrono::Local::now().year()
 }
 
+/// Returns the current date, or, if the `SOURCE_DATE_EPOCH` environment
+/// variable is set, the UTC date of the timestamp given therein
+pub(crate) fn today() -> anyhow::Result<NaiveDate> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(s) => epoch_date(&s),
+        Err(std::env::VarError::NotPresent) => Ok(chrono::Local::now().date_naive()),
+        Err(e) => Err(e).context("Failed to read SOURCE_DATE_EPOCH"),
+    }
+}
+
+/// Parse a `SOURCE_DATE_EPOCH` value and return its UTC date
+fn epoch_date(s: &str) -> anyhow::Result<NaiveDate> {
+    let Ok(secs) = s.trim().parse::<i64>() else {
+        bail!("Invalid SOURCE_DATE_EPOCH value: {s:?}");
+    };
+    let Some(dt) = chrono::DateTime::from_timestamp(secs, 0) else {
+        bail!("SOURCE_DATE_EPOCH value out of range: {s:?}");
+    };
+    Ok(dt.date_naive())
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct StringLines {
     content: String,
@@ -147,6 +169,12 @@ fn rust_version(input: &mut &str) -> ModalResult<RustVersion> {
     .parse_next(input)
 }
 
+/// Parse a version given on the command line, with an optional leading `v`
+pub(crate) fn parse_v_version(value: &str) -> Result<Version, cargo_metadata::semver::Error> {
+    let value = value.strip_prefix('v').unwrap_or(value);
+    value.parse::<Version>()
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) enum Bump {
     Major,
@@ -162,6 +190,87 @@ pub(crate) fn bump_version(v: Version, level: Bump) -> Version {
     }
 }
 
+/// The kinds of prerelease in a prerelease series, in order
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, clap::ValueEnum)]
+pub(crate) enum PreLevel {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl PreLevel {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PreLevel::Alpha => "alpha",
+            PreLevel::Beta => "beta",
+            PreLevel::Rc => "rc",
+        }
+    }
+
+    /// Parse a prerelease segment of the form `{level}.{number}` (or just
+    /// `{level}`, treated as number 0)
+    fn parse_prerelease(pre: &Prerelease) -> Option<(PreLevel, u64)> {
+        let (level, number) = match pre.as_str().split_once('.') {
+            Some((level, number)) => (level, number.parse::<u64>().ok()?),
+            None => (pre.as_str(), 0),
+        };
+        let level = match level {
+            "alpha" => PreLevel::Alpha,
+            "beta" => PreLevel::Beta,
+            "rc" => PreLevel::Rc,
+            _ => return None,
+        };
+        Some((level, number))
+    }
+
+    /// Returns the release version of `base` with a prerelease segment of
+    /// this level and the given number
+    pub(crate) fn version(self, base: &Version, number: u64) -> Version {
+        let mut v = Version::new(base.major, base.minor, base.patch);
+        v.pre = Prerelease::new(&format!("{self}.{number}"))
+            .expect("prerelease level & number should be a valid prerelease identifier");
+        v
+    }
+}
+
+impl fmt::Display for PreLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Bump `v` to the next prerelease at level `pre`.
+///
+/// If `level` is given, the release version of `v` is bumped at that level
+/// and the first prerelease of the result is returned.  Otherwise, if `v` is
+/// a release, the first prerelease of the next minor version is returned, and
+/// if `v` is a prerelease, the prerelease number is incremented (if `pre` is
+/// the same level as `v`'s prerelease) or reset to 1 (if `pre` is a higher
+/// level).
+pub(crate) fn bump_prerelease(
+    v: &Version,
+    level: Option<Bump>,
+    pre: PreLevel,
+) -> anyhow::Result<Version> {
+    let release = Version::new(v.major, v.minor, v.patch);
+    if let Some(level) = level {
+        return Ok(pre.version(&bump_version(release, level), 1));
+    }
+    if v.pre.is_empty() {
+        return Ok(pre.version(&bump_version(release, Bump::Minor), 1));
+    }
+    let Some((current, number)) = PreLevel::parse_prerelease(&v.pre) else {
+        bail!("Cannot determine prerelease level of version {v}");
+    };
+    match pre.cmp(&current) {
+        std::cmp::Ordering::Greater => Ok(pre.version(&release, 1)),
+        std::cmp::Ordering::Equal => Ok(pre.version(&release, number + 1)),
+        std::cmp::Ordering::Less => {
+            bail!("Cannot go from version {v} to lower prerelease level {pre}")
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct CopyrightLine {
     prefix: String,
@@ -315,6 +424,23 @@ mod tests {
     use predicates::prelude::*;
     use rstest::rstest;
 
+    #[rstest]
+    #[case("0", NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())]
+    #[case("1700000000", NaiveDate::from_ymd_opt(2023, 11, 14).unwrap())]
+    #[case(" 1710460800\n", NaiveDate::from_ymd_opt(2024, 3, 15).unwrap())]
+    fn test_epoch_date(#[case] s: &str, #[case] date: NaiveDate) {
+        assert_eq!(epoch_date(s).unwrap(), date);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("2024-03-15")]
+    #[case("1.5")]
+    #[case("99999999999999999")]
+    fn test_epoch_date_err(#[case] s: &str) {
+        assert!(epoch_date(s).is_err());
+    }
+
     #[test]
     fn string_lines() {
         let mut iter = StringLines::new("foo\r\nbar\n\nbaz\n".into());
@@ -432,6 +558,30 @@ mod tests {
         assert_eq!(bump_version(v, level), bumped);
     }
 
+    #[rstest]
+    #[case("1.2.0", None, PreLevel::Rc, "1.3.0-rc.1")]
+    #[case("1.3.0-rc.1", None, PreLevel::Rc, "1.3.0-rc.2")]
+    #[case("1.3.0-alpha.3", None, PreLevel::Beta, "1.3.0-beta.1")]
+    #[case("1.3.0-rc", None, PreLevel::Rc, "1.3.0-rc.1")]
+    #[case("1.2.0", Some(Bump::Major), PreLevel::Alpha, "2.0.0-alpha.1")]
+    #[case("1.2.0", Some(Bump::Patch), PreLevel::Beta, "1.2.1-beta.1")]
+    #[case("1.3.0-rc.2", Some(Bump::Major), PreLevel::Alpha, "2.0.0-alpha.1")]
+    fn test_bump_prerelease(
+        #[case] v: Version,
+        #[case] level: Option<Bump>,
+        #[case] pre: PreLevel,
+        #[case] bumped: Version,
+    ) {
+        assert_eq!(bump_prerelease(&v, level, pre).unwrap(), bumped);
+    }
+
+    #[rstest]
+    #[case("1.3.0-rc.1", PreLevel::Alpha)]
+    #[case("1.3.0-dev", PreLevel::Rc)]
+    fn test_bump_prerelease_err(#[case] v: Version, #[case] pre: PreLevel) {
+        assert!(bump_prerelease(&v, None, pre).is_err());
+    }
+
     #[test]
     fn test_copyright_line_one_year() {
         let s = "Copyright (c) 2023 John T. Wodder II";
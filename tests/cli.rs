@@ -1,4 +1,6 @@
 mod util;
+#[cfg(unix)]
+use crate::util::ReleaseRepo;
 use crate::util::{CmpDirtrees, opt_subdir, unzip};
 use assert_cmd::{Command, cargo::cargo_bin_cmd};
 use cfg_if::cfg_if;
@@ -256,6 +258,80 @@ fn new_description() {
         .assert_eq();
 }
 
+#[rstest]
+#[case("version", vec!["1.0.0"])]
+#[case("version", vec!["v1.0.0"])]
+#[case("major", vec!["--major"])]
+#[case("workspace", vec!["--workspace", "1.0.0"])]
+#[case("keep-a-changelog", Vec::new())]
+fn begin_dev(#[case] case: &str, #[case] args: Vec<&str>) {
+    let tmp_path = tempdir().unwrap();
+    let workdir = tmp_path.path().join("work");
+    let gooddir = tmp_path.path().join("good");
+    unzip(
+        Path::new(DATA_DIR)
+            .join("begin-dev")
+            .join(case)
+            .join("before.zip"),
+        &workdir,
+    )
+    .unwrap();
+    cargo_bin_cmd!("rsrepo")
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("begin-dev")
+        .args(args)
+        .current_dir(&workdir)
+        .assert()
+        .success();
+    unzip(
+        Path::new(DATA_DIR)
+            .join("begin-dev")
+            .join(case)
+            .join("after.zip"),
+        &gooddir,
+    )
+    .unwrap();
+    CmpDirtrees::new(gooddir, workdir).assert_eq();
+}
+
+#[rstest]
+#[case("category", vec!["--category", "fixed", "Fixed a thing"])]
+#[case("released", vec!["Support frobnication"])]
+fn changelog_add(#[case] case: &str, #[case] args: Vec<&str>) {
+    let tmp_path = tempdir().unwrap();
+    let workdir = tmp_path.path().join("work");
+    let gooddir = tmp_path.path().join("good");
+    unzip(
+        Path::new(DATA_DIR)
+            .join("changelog-add")
+            .join(case)
+            .join("before.zip"),
+        &workdir,
+    )
+    .unwrap();
+    cargo_bin_cmd!("rsrepo")
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("changelog")
+        .arg("add")
+        .args(args)
+        .current_dir(&workdir)
+        .assert()
+        .success();
+    unzip(
+        Path::new(DATA_DIR)
+            .join("changelog-add")
+            .join(case)
+            .join("after.zip"),
+        &gooddir,
+    )
+    .unwrap();
+    CmpDirtrees::new(gooddir, workdir).assert_eq();
+}
+
 #[rstest]
 #[case("plain", Vec::new(), None)]
 #[case("no-entry", Vec::new(), None)]
@@ -384,6 +460,7 @@ fn inspect(
 #[case("lib.zip", vec!["--codecov-token=hunter2", "fibseqlib"], "lib-cli-name.json")]
 #[case("bin.zip", vec!["--no-codecov-token"], "bin.json")]
 #[case("bin.zip", vec!["--no-codecov-token", "fibseqcli"], "bin-cli-name.json")]
+#[case("bin.zip", vec!["--no-codecov-token", "--remote", "upstream"], "bin-remote.json")]
 #[case("workspace.zip", vec!["--no-codecov-token"], "workspace.json")]
 #[case("workspace.zip", vec!["--no-codecov-token", "fibstuff"], "workspace-cli-name.json")]
 #[case("virtual.zip", vec!["--no-codecov-token"], "virtual.json")]
@@ -429,3 +506,348 @@ fn mkgithub(#[case] zipfile: &str, #[case] args: Vec<&str>, #[case] jsonfile: &s
         .success()
         .stdout(expected);
 }
+
+#[rstest]
+#[case("package.zip", None, Vec::new(), &["v0.1.0"], "package.json")]
+#[case("package.zip", None, vec!["--major"], &["v0.1.0"], "package-major.json")]
+#[case("package.zip", None, vec!["--sign", "ssh", "--sign-commit"], &["v0.1.0"], "package-ssh.json")]
+#[case("package.zip", None, vec!["--maintenance", "--patch"], &["v0.1.0"], "package-maintenance.json")]
+#[case("package.zip", None, vec!["--pre", "rc"], &["v0.1.0"], "package-pre.json")]
+#[case("package-major-dev.zip", None, vec!["--pre", "rc"], &["v0.1.0"], "package-major-dev-pre.json")]
+#[case("package.zip", None, vec!["--summary", "Add more features"], &["v0.1.0"], "package-summary.json")]
+#[case("package.zip", None, vec!["--draft", "0.1.1"], &["v0.1.0", "v1.0.0"], "package-draft-patch.json")]
+#[case("package.zip", None, vec!["--date", "2024-03-14"], &["v0.1.0"], "package-date.json")]
+#[case("package-internal.zip", None, Vec::new(), &["v0.1.0"], "package-internal.json")]
+#[case("workspace.zip", Some("crates/core"), Vec::new(), &["fibcore/v0.1.0", "fibcore/v0.2.0"], "workspace.json")]
+#[case("workspace.zip", None, vec!["-p", "fibcli"], &[], "workspace-cli.json")]
+#[case("workspace.zip", None, vec!["-p", "fibcli", "-p", "fibcore"], &[], "workspace-both.json")]
+#[case("workspace.zip", None, vec!["--changed"], &["fibcore/v0.1.0", "fibcore/v0.2.0"], "workspace-cli.json")]
+#[case("workspace.zip", None, vec!["--changed"], &[], "workspace-both.json")]
+#[case("workspace-xtask.zip", None, vec!["--changed"], &[], "workspace-both.json")]
+#[case("workspace-dev-cycle.zip", None, vec!["-p", "fibcore"], &[], "workspace-dev-cycle.json")]
+#[case("workspace-dev-cycle.zip", None, vec!["-p", "fibcore", "-p", "fibtest"], &[], "workspace-dev-cycle-both.json")]
+fn release_plan(
+    #[case] zipfile: &str,
+    #[case] subdir: Option<&str>,
+    #[case] args: Vec<&str>,
+    #[case] tags: &[&str],
+    #[case] jsonfile: &str,
+) {
+    let tmp_path = tempdir().unwrap();
+    unzip(
+        Path::new(DATA_DIR).join("release").join(zipfile),
+        tmp_path.path(),
+    )
+    .unwrap();
+    Command::new("git")
+        .arg("init")
+        .arg("-b")
+        .arg("main")
+        .current_dir(tmp_path.path())
+        .assert()
+        .success();
+    Command::new("git")
+        .arg("add")
+        .arg(".")
+        .current_dir(tmp_path.path())
+        .assert()
+        .success();
+    Command::new("git")
+        .arg("commit")
+        .arg("-m")
+        .arg("Initial commit")
+        .env("GIT_AUTHOR_DATE", "2023-06-01T12:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2023-06-01T12:00:00Z")
+        .current_dir(tmp_path.path())
+        .assert()
+        .success();
+    for tag in tags {
+        Command::new("git")
+            .arg("tag")
+            .arg(tag)
+            .current_dir(tmp_path.path())
+            .assert()
+            .success();
+    }
+    Command::new("git")
+        .arg("remote")
+        .arg("add")
+        .arg("origin")
+        .arg("https://github.com/octocat/foobar.git")
+        .current_dir(tmp_path.path())
+        .assert()
+        .success();
+    let expected =
+        fs_err::read_to_string(Path::new(DATA_DIR).join("release").join(jsonfile)).unwrap();
+    cargo_bin_cmd!("rsrepo")
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("release")
+        .arg("--plan-only")
+        .args(args)
+        // 2024-06-14T12:00:00Z
+        .env("SOURCE_DATE_EPOCH", "1718366400")
+        .current_dir(opt_subdir(tmp_path.path(), subdir))
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn unrelease(#[case] pushed: bool) {
+    let tmp_path = tempdir().unwrap();
+    let repo = tmp_path.path().join("foobar");
+    let remote = tmp_path.path().join("remote.git");
+    unzip(
+        Path::new(DATA_DIR).join("release").join("package.zip"),
+        &repo,
+    )
+    .unwrap();
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&repo)
+            .assert()
+            .success()
+    };
+    Command::new("git")
+        .arg("init")
+        .arg("--bare")
+        .arg(&remote)
+        .assert()
+        .success();
+    git(&["init", "-b", "main"]);
+    git(&["add", "."]);
+    git(&["commit", "-m", "Initial commit"]);
+    git(&["remote", "add", "origin", remote.to_str().unwrap()]);
+    git(&["push", "-u", "origin", "main"]);
+    let initial = git(&["rev-parse", "HEAD"]).get_output().stdout.clone();
+    git(&["commit", "--allow-empty", "-m", "v0.2.0 — Feature release"]);
+    git(&["tag", "-m", "Version 0.2.0", "v0.2.0"]);
+    if pushed {
+        git(&["push", "--follow-tags"]);
+    }
+    let cmd = cargo_bin_cmd!("rsrepo")
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("unrelease")
+        .current_dir(&repo)
+        .assert();
+    if pushed {
+        cmd.failure().stderr(predicates::str::contains(
+            "Tag v0.2.0 has already been pushed to origin",
+        ));
+        git(&["rev-parse", "--verify", "refs/tags/v0.2.0"]);
+    } else {
+        cmd.success();
+        let head = git(&["rev-parse", "HEAD"]).get_output().stdout.clone();
+        assert_eq!(head, initial);
+        Command::new("git")
+            .args(["rev-parse", "--verify", "--quiet", "refs/tags/v0.2.0"])
+            .current_dir(&repo)
+            .assert()
+            .failure();
+    }
+}
+
+#[cfg(unix)]
+fn release(repo: &ReleaseRepo, args: &[&str]) -> assert_cmd::assert::Assert {
+    repo.rsrepo()
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("release")
+        .args(args)
+        .assert()
+}
+
+#[cfg(unix)]
+#[test]
+fn release_execute() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "",
+    );
+    release(&repo, &["--skip-checks", "--sign", "none", "--no-edit"]).success();
+    assert!(!repo.journal_exists());
+    let head = repo.git(&["rev-parse", "HEAD"]);
+    assert_eq!(repo.git(&["log", "-1", "--format=%s"]), "v0.2.0");
+    assert_eq!(repo.remote_ref("refs/heads/main"), Some(head.clone()));
+    assert_eq!(repo.remote_ref("refs/tags/v0.2.0^{commit}"), Some(head));
+    let manifest = fs_err::read_to_string(repo.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("version = \"0.3.0-dev\""));
+}
+
+#[cfg(unix)]
+#[test]
+fn release_resume() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "post-release = \"git rev-parse --verify --quiet refs/tags/post-release-ok\"\n",
+    );
+    release(&repo, &["--skip-checks", "--sign", "none", "--no-edit"]).failure();
+    assert!(repo.journal_exists());
+    let release_commit = repo.git(&["rev-parse", "v0.2.0^{commit}"]);
+    assert_eq!(
+        repo.remote_ref("refs/tags/v0.2.0^{commit}"),
+        Some(release_commit)
+    );
+    release(&repo, &["--resume"])
+        .failure()
+        .stderr(predicates::str::contains("post-release"));
+    assert!(repo.journal_exists());
+    repo.git(&["tag", "post-release-ok"]);
+    release(&repo, &["--resume"]).success();
+    assert!(!repo.journal_exists());
+    release(&repo, &["--resume"])
+        .failure()
+        .stderr(predicates::str::contains(
+            "No interrupted release to resume",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn release_rollback_pre_commit() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "pre-commit = \"git rev-parse --verify --quiet refs/tags/nonexistent\"\n",
+    );
+    let initial = repo.git(&["rev-parse", "HEAD"]);
+    release(&repo, &["--skip-checks", "--sign", "none", "--no-edit"])
+        .failure()
+        .stderr(predicates::str::contains("rolling back release of foobar"))
+        .stderr(predicates::str::contains(
+            "The following packages were not released: foobar 0.2.0",
+        ));
+    assert!(!repo.journal_exists());
+    assert_eq!(repo.git(&["rev-parse", "HEAD"]), initial);
+    assert_eq!(repo.git(&["status", "--porcelain"]), "");
+    assert_eq!(repo.git(&["tag", "--list"]), "");
+    assert_eq!(repo.remote_ref("refs/heads/main"), Some(initial));
+    assert_eq!(repo.remote_ref("refs/tags/v0.2.0"), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn release_no_push_then_push() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "",
+    );
+    let initial = repo.git(&["rev-parse", "HEAD"]);
+    release(
+        &repo,
+        &["--skip-checks", "--sign", "none", "--no-edit", "--no-push"],
+    )
+    .success();
+    assert!(repo.journal_exists());
+    let release_commit = repo.git(&["rev-parse", "v0.2.0^{commit}"]);
+    assert_eq!(repo.git(&["rev-parse", "HEAD"]), release_commit);
+    assert_eq!(repo.remote_ref("refs/heads/main"), Some(initial));
+    assert_eq!(repo.remote_ref("refs/tags/v0.2.0"), None);
+    release(&repo, &["--push"]).success();
+    assert!(!repo.journal_exists());
+    assert_eq!(
+        repo.remote_ref("refs/heads/main"),
+        Some(release_commit.clone())
+    );
+    assert_eq!(
+        repo.remote_ref("refs/tags/v0.2.0^{commit}"),
+        Some(release_commit)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn release_ssh_without_allowed_signers() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "",
+    );
+    let initial = repo.git(&["rev-parse", "HEAD"]);
+    repo.rsrepo()
+        .arg("--log-level=TRACE")
+        .arg("--config")
+        .arg(Path::new(DATA_DIR).join("config.toml"))
+        .arg("release")
+        .arg("--skip-checks")
+        .arg("--sign")
+        .arg("ssh")
+        .arg("--no-edit")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("gpg.ssh.allowedSignersFile"));
+    assert_eq!(repo.git(&["rev-parse", "HEAD"]), initial);
+    assert_eq!(repo.git(&["status", "--porcelain"]), "");
+    assert!(!repo.journal_exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn release_without_config_file() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("package-unpublished.zip"),
+        "",
+    );
+    let home = tempdir().unwrap();
+    repo.rsrepo()
+        .arg("release")
+        .arg("--plan-only")
+        .env("HOME", home.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(r#""method": "git""#));
+}
+
+#[cfg(unix)]
+#[test]
+fn release_multiple_commit_messages() {
+    let repo = ReleaseRepo::new(
+        Path::new(DATA_DIR)
+            .join("release")
+            .join("workspace-unpublished.zip"),
+        "",
+    );
+    release(
+        &repo,
+        &[
+            "-p",
+            "fibcore",
+            "-p",
+            "fibcli",
+            "--skip-checks",
+            "--sign",
+            "none",
+            "--no-edit",
+            "--no-push",
+        ],
+    )
+    .success();
+    assert_eq!(
+        repo.git(&["show", "-s", "--format=%B", "fibcore/v0.3.0^{commit}"]),
+        "fibcore v0.3.0\n\n- Computed more numbers"
+    );
+    assert_eq!(
+        repo.git(&["show", "-s", "--format=%B", "fibcli/v0.1.0^{commit}"]),
+        "fibcli v0.1.0\n\n- Initial release\n- Increase `fibcore` dependency to `0.3.0`"
+    );
+}
@@ -1,4 +1,6 @@
 #![cfg(test)]
+#[cfg(unix)]
+use assert_cmd::{Command, cargo::cargo_bin_cmd};
 use fs_err::{read_dir, read_to_string};
 use similar::Algorithm;
 use similar::udiff::unified_diff;
@@ -147,3 +149,112 @@ pub(crate) fn opt_subdir<'a>(path: &'a Path, subdir: Option<&str>) -> std::borro
         None => path.into(),
     }
 }
+
+/// A Git repository containing an unpublished package, with an `origin`
+/// remote whose GitHub URL is served from a local bare repository by way of a
+/// fake SSH command
+#[cfg(unix)]
+#[derive(Debug)]
+pub(crate) struct ReleaseRepo {
+    _tmp_path: tempfile::TempDir,
+    path: PathBuf,
+    ssh_root: PathBuf,
+}
+
+#[cfg(unix)]
+impl ReleaseRepo {
+    pub(crate) fn new<P: Into<PathBuf>>(zippath: P, hooks: &str) -> ReleaseRepo {
+        let tmp_path = tempfile::tempdir().unwrap();
+        let repo = ReleaseRepo {
+            path: tmp_path.path().join("foobar"),
+            ssh_root: tmp_path.path().join("ssh"),
+            _tmp_path: tmp_path,
+        };
+        unzip(zippath, repo.path()).unwrap();
+        if !hooks.is_empty() {
+            let manifest = repo.path().join("Cargo.toml");
+            let mut src = read_to_string(&manifest).unwrap();
+            src.push_str("\n[package.metadata.rsrepo.hooks]\n");
+            src.push_str(hooks);
+            fs_err::write(&manifest, src).unwrap();
+        }
+        Command::new("git")
+            .arg("init")
+            .arg("--bare")
+            .arg(repo.remote())
+            .assert()
+            .success();
+        repo.git(&["init", "-b", "main"]);
+        repo.git(&["add", "."]);
+        repo.git(&["commit", "-m", "Initial commit"]);
+        repo.git(&[
+            "remote",
+            "add",
+            "origin",
+            "git@github.com:octocat/foobar.git",
+        ]);
+        repo.git(&["push", "origin", "main"]);
+        repo
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn remote(&self) -> PathBuf {
+        self.ssh_root.join("octocat").join("foobar.git")
+    }
+
+    fn ssh_command(&self) -> String {
+        format!(
+            "sh -c 'cd \"$0\" && eval \"$2\"' '{}'",
+            self.ssh_root.display()
+        )
+    }
+
+    pub(crate) fn git(&self, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .env("GIT_SSH_VARIANT", "simple")
+            .env("GIT_SSH_COMMAND", self.ssh_command())
+            .current_dir(self.path())
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap().trim().to_owned()
+    }
+
+    /// Returns the object ID that `refname` points to in the remote, if any
+    pub(crate) fn remote_ref(&self, refname: &str) -> Option<String> {
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(self.remote())
+            .args(["rev-parse", "--verify", "--quiet", refname])
+            .output()
+            .unwrap();
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8(output.stdout).unwrap().trim().to_owned())
+    }
+
+    pub(crate) fn journal_exists(&self) -> bool {
+        self.path()
+            .join(".git")
+            .join("rsrepo-release.json")
+            .exists()
+    }
+
+    /// Returns a command for running `rsrepo` in the repository with Git
+    /// configured to use the fake SSH command
+    pub(crate) fn rsrepo(&self) -> Command {
+        let mut cmd = cargo_bin_cmd!("rsrepo");
+        cmd.env("GIT_SSH_VARIANT", "simple")
+            .env("GIT_SSH_COMMAND", self.ssh_command())
+            .env("GH_TOKEN", "dummy")
+            .current_dir(self.path());
+        cmd
+    }
+}